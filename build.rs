@@ -1,6 +1,6 @@
 mod generate_ast;
 use generate_ast::*;
 
-fn main() -> () {
+fn main() {
     generate_ast(&"src".to_string()).unwrap();
 }
@@ -12,23 +12,58 @@ pub fn generate_ast(output_dir: &String) -> io::Result<()> {
     define_ast(
         output_dir,
         &"Expr".to_string(),
-        &vec!["error", "tokens::token", "object", "errors::syntax_error"],
-        &vec![
+        &[
+            "crate::tokens::token::*",
+            "crate::object::*",
+            "crate::errors::syntax_error::*",
+            "std::cell::Cell",
+        ],
+        &[
+            "Assign   : Token name, Box<Expr> value, Cell<Option<usize>> depth".to_string(),
             "Binary   : Box<Expr> left, Token operator, Box<Expr> right".to_string(),
+            "Call     : Box<Expr> callee, Token paren, Vec<Expr> arguments, bool safe".to_string(),
+            "Compound : Box<Expr> target, Token operator, Box<Expr> value".to_string(),
+            "Conditional : Box<Expr> condition, Box<Expr> then_branch, Box<Expr> else_branch".to_string(),
+            "Get      : Box<Expr> object, Token name, bool safe".to_string(),
             "Grouping : Box<Expr> expression".to_string(),
+            "Index    : Box<Expr> object, Token bracket, Box<Expr> index".to_string(),
+            "Interpolation : Vec<Expr> parts".to_string(),
+            "List     : Vec<Expr> elements".to_string(),
             "Literal  : Option<Object> value".to_string(),
+            "Logical  : Box<Expr> left, Token operator, Box<Expr> right".to_string(),
+            "Map      : Token brace, Vec<Expr> keys, Vec<Expr> values".to_string(),
+            "SafeChain : Box<Expr> expression".to_string(),
+            "Set      : Box<Expr> object, Token name, Box<Expr> value".to_string(),
+            "SetIndex : Box<Expr> object, Token bracket, Box<Expr> index, Box<Expr> value".to_string(),
+            "Slice    : Box<Expr> object, Token bracket, Option<Box<Expr>> start, Option<Box<Expr>> end".to_string(),
+            "Super    : Token keyword, Token method, Cell<Option<usize>> depth".to_string(),
+            "This     : Token keyword, Cell<Option<usize>> depth".to_string(),
             "Unary    : Token operator, Box<Expr> right".to_string(),
-            "Variable : Token name".to_string(),
+            "Update   : Box<Expr> target, Token operator, bool prefix".to_string(),
+            "Variable : Token name, Cell<Option<usize>> depth".to_string(),
         ],
     )?;
     define_ast(
         output_dir,
         &"Stmt".to_string(),
-        &vec!["error", "expr", "tokens::token", "errors::syntax_error"],
-        &vec![
+        &[
+            "crate::expr::*",
+            "crate::tokens::token::*",
+            "crate::errors::syntax_error::*",
+            "std::rc::Rc",
+        ],
+        &[
+            "Block      : Vec<Stmt> statements".to_string(),
+            "Break      : Token keyword, Option<Token> label".to_string(),
+            "Class      : Token name, Option<Expr> superclass, Vec<FunctionStmt> methods".to_string(),
+            "Continue   : Token keyword, Option<Token> label".to_string(),
             "Expression : Expr expression".to_string(),
+            "Function   : Token name, Rc<Vec<Token>> params, Rc<Vec<Stmt>> body".to_string(),
+            "If         : Expr condition, Box<Stmt> then_branch, Option<Box<Stmt>> else_branch".to_string(),
             "Print      : Expr expression".to_string(),
-            "Var        : Token name, Option<Expr> initializer".to_string(),
+            "Return     : Token keyword, Option<Expr> value".to_string(),
+            "Var        : Token name, Option<Expr> initializer, bool constant".to_string(),
+            "While      : Expr condition, Box<Stmt> body, Option<Expr> increment, Option<Token> label".to_string(),
         ],
     )?;
     Ok(())
@@ -45,7 +80,7 @@ fn define_ast(
     let mut tree_types = Vec::new();
 
     for i in imports {
-        writeln!(file, "use crate::{}::*;", i)?;
+        writeln!(file, "use {};", i)?;
     }
 
     for ttype in types {
@@ -64,41 +99,37 @@ fn define_ast(
         });
     }
 
-    write!(file, "\npub enum {base_name} {{\n")?;
+    writeln!(file, "\npub enum {base_name} {{")?;
     for t in &tree_types {
-        write!(file, "    {}({}),\n", t.base_class_name, t.class_name)?;
+        writeln!(file, "    {}({}),", t.base_class_name, t.class_name)?;
     }
     write!(file, "}}\n\n")?;
 
-    write!(file, "impl {} {{\n", base_name)?;
-    write!(file, "    pub fn accept<T>(&self, {}_visitor: &dyn {base_name}Visitor<T>) -> Result<T, SyntaxError> {{\n", base_name.to_lowercase())?;
-    write!(file, "        match self {{\n")?;
+    writeln!(file, "impl {} {{", base_name)?;
+    writeln!(file, "    pub fn accept<T>(&self, {}_visitor: &dyn {base_name}Visitor<T>) -> Result<T, SyntaxError> {{", base_name.to_lowercase())?;
+    writeln!(file, "        match self {{")?;
     for t in &tree_types {
-        write!(
-            file,
-            "            {}::{}(v) => v.accept({}_visitor),\n",
+        writeln!(file, "            {}::{}(v) => v.accept({}_visitor),",
             base_name,
             t.base_class_name,
             base_name.to_lowercase()
         )?;
     }
-    write!(file, "        }}\n")?;
-    write!(file, "    }}\n")?;
+    writeln!(file, "        }}")?;
+    writeln!(file, "    }}")?;
     write!(file, "}}\n\n")?;
 
     for t in &tree_types {
-        write!(file, "pub struct {} {{\n", t.class_name)?;
+        writeln!(file, "pub struct {} {{", t.class_name)?;
         for f in &t.fields {
-            write!(file, "    pub {},\n", f)?;
+            writeln!(file, "    pub {},", f)?;
         }
         write!(file, "}}\n\n")?;
     }
 
-    write!(file, "pub trait {}Visitor<T> {{\n", base_name)?;
+    writeln!(file, "pub trait {}Visitor<T> {{", base_name)?;
     for t in &tree_types {
-        write!(
-            file,
-            "    fn visit_{}_{}(&self, expr: &{}) -> Result<T, SyntaxError>;\n",
+        writeln!(file, "    fn visit_{}_{}(&self, expr: &{}) -> Result<T, SyntaxError>;",
             t.base_class_name.to_lowercase(),
             base_name.to_lowercase(),
             t.class_name
@@ -107,19 +138,15 @@ fn define_ast(
     write!(file, "}}\n\n")?;
 
     for t in &tree_types {
-        write!(file, "impl {} {{\n", t.class_name)?;
-        write!(
-            file,
-            "    pub fn accept<T>(&self, visitor: &dyn {}Visitor<T>) -> Result<T, SyntaxError> {{\n",
+        writeln!(file, "impl {} {{", t.class_name)?;
+        writeln!(file, "    pub fn accept<T>(&self, visitor: &dyn {}Visitor<T>) -> Result<T, SyntaxError> {{",
             base_name
         )?;
-        write!(
-            file,
-            "        visitor.visit_{}_{}(self)\n",
+        writeln!(file, "        visitor.visit_{}_{}(self)",
             t.base_class_name.to_lowercase(),
             base_name.to_lowercase()
         )?;
-        write!(file, "    }}\n")?;
+        writeln!(file, "    }}")?;
         write!(file, "}}\n\n")?;
     }
     Ok(())
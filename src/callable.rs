@@ -0,0 +1,22 @@
+use std::rc::Rc;
+
+use crate::errors::syntax_error::SyntaxError;
+use crate::interpreter::*;
+use crate::object::*;
+use crate::tokens::token::*;
+
+/// Anything that can sit on the left of a call expression. The
+/// interpreter checks the argument count against `arity` before
+/// calling, so implementations can index into `arguments` freely.
+/// Calls receive the shared handle so that a class can hand itself to
+/// the instances it creates, and the call's closing parenthesis so that
+/// errors raised by the callee itself can point at the call site.
+pub trait CedarCallable {
+    fn call(
+        self: Rc<Self>,
+        interpreter: &Interpreter,
+        arguments: Vec<Object>,
+        paren: &Token,
+    ) -> Result<Object, SyntaxError>;
+    fn arity(&self) -> usize;
+}
@@ -0,0 +1,91 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::cedar_function::*;
+use crate::cedar_instance::*;
+use crate::errors::syntax_error::SyntaxError;
+use crate::interpreter::*;
+use crate::object::*;
+use crate::tokens::token::*;
+
+pub struct CedarClass {
+    name: String,
+    superclass: Option<Rc<CedarClass>>,
+    methods: HashMap<String, Rc<CedarFunction>>,
+}
+
+impl CedarClass {
+    pub fn new(
+        name: &str,
+        superclass: Option<Rc<CedarClass>>,
+        methods: HashMap<String, Rc<CedarFunction>>,
+    ) -> CedarClass {
+        CedarClass {
+            name: name.to_string(),
+            superclass,
+            methods,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Looks for a method on this class first and then up the
+    /// superclass chain, so subclasses override what they inherit.
+    pub fn find_method(&self, name: &str) -> Option<Rc<CedarFunction>> {
+        match self.methods.get(name) {
+            Some(method) => Some(Rc::clone(method)),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name)),
+        }
+    }
+}
+
+impl CedarCallable for CedarClass {
+    /// Calling a class creates a new instance and, when the class has
+    /// an `init` method, runs it bound to that instance with the call's
+    /// arguments.
+    fn call(
+        self: Rc<Self>,
+        interpreter: &Interpreter,
+        arguments: Vec<Object>,
+        paren: &Token,
+    ) -> Result<Object, SyntaxError> {
+        let instance = Rc::new(CedarInstance::new(Rc::clone(&self)));
+
+        if let Some(initializer) = self.find_method("init") {
+            Rc::new(initializer.bind(&instance)).call(interpreter, arguments, paren)?;
+        }
+
+        Ok(Object::Instance(instance))
+    }
+
+    fn arity(&self) -> usize {
+        self.find_method("init")
+            .map_or(0, |initializer| initializer.arity())
+    }
+}
+
+impl fmt::Display for CedarClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<class {}>", self.name)
+    }
+}
+
+impl fmt::Debug for CedarClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Classes compare by identity.
+impl PartialEq for CedarClass {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
@@ -0,0 +1,110 @@
+use core::fmt;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::cedar_instance::*;
+use crate::environment::*;
+use crate::errors::syntax_error::SyntaxError;
+use crate::interpreter::*;
+use crate::object::*;
+use crate::stmt::*;
+use crate::tokens::token::*;
+
+pub struct CedarFunction {
+    name: Token,
+    params: Rc<Vec<Token>>,
+    body: Rc<Vec<Stmt>>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl CedarFunction {
+    /// Creates a function value that keeps hold of the environment it
+    /// was declared in, so its body can keep using the variables that
+    /// were in scope even after that scope has been exited.
+    pub fn new(
+        declaration: &FunctionStmt,
+        closure: &Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> CedarFunction {
+        CedarFunction {
+            name: declaration.name.clone(),
+            params: Rc::clone(&declaration.params),
+            body: Rc::clone(&declaration.body),
+            closure: Rc::clone(closure),
+            is_initializer,
+        }
+    }
+
+    /// Produces a copy of this method whose closure has `this` bound to
+    /// the given instance, one scope inside the method's own closure.
+    pub fn bind(&self, instance: &Rc<CedarInstance>) -> CedarFunction {
+        let mut environment = Environment::new_enclosing(Rc::clone(&self.closure));
+        environment.define("this", Object::Instance(Rc::clone(instance)));
+
+        CedarFunction {
+            name: self.name.clone(),
+            params: Rc::clone(&self.params),
+            body: Rc::clone(&self.body),
+            closure: Rc::new(RefCell::new(environment)),
+            is_initializer: self.is_initializer,
+        }
+    }
+}
+
+impl CedarCallable for CedarFunction {
+    /// Binds each argument to its parameter in a fresh scope and runs
+    /// the body there. A `ret` anywhere in the body unwinds back to this
+    /// point carrying its value; falling off the end yields null, except
+    /// for `init` which always hands back the instance.
+    fn call(
+        self: Rc<Self>,
+        interpreter: &Interpreter,
+        arguments: Vec<Object>,
+        _paren: &Token,
+    ) -> Result<Object, SyntaxError> {
+        let mut environment = Environment::new_enclosing(Rc::clone(&self.closure));
+        for (param, argument) in self.params.iter().zip(arguments) {
+            environment.define(&param.lexeme, argument);
+        }
+
+        let result = match interpreter.execute_block(&self.body, environment) {
+            Ok(()) => Object::Nil,
+            Err(error) => error.take_return_value()?,
+        };
+
+        if self.is_initializer {
+            return Ok(self
+                .closure
+                .borrow()
+                .get_local("this")
+                .unwrap_or(Object::Nil));
+        }
+        Ok(result)
+    }
+
+    fn arity(&self) -> usize {
+        self.params.len()
+    }
+}
+
+impl fmt::Display for CedarFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<func {}>", self.name.lexeme)
+    }
+}
+
+impl fmt::Debug for CedarFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Two function values are equal only when they come from the same
+/// declaration evaluated in the same environment.
+impl PartialEq for CedarFunction {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.body, &other.body) && Rc::ptr_eq(&self.closure, &other.closure)
+    }
+}
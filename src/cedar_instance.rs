@@ -0,0 +1,68 @@
+use core::fmt;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::cedar_class::*;
+use crate::errors::syntax_error::{SyntaxError, SyntaxErrorTypes};
+use crate::object::*;
+use crate::tokens::token::*;
+
+pub struct CedarInstance {
+    class: Rc<CedarClass>,
+    fields: RefCell<HashMap<String, Object>>,
+}
+
+impl CedarInstance {
+    pub fn new(class: Rc<CedarClass>) -> CedarInstance {
+        CedarInstance {
+            class,
+            fields: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Looks up a property on the instance. Fields shadow methods, and a
+    /// method found on the class comes back bound to this instance.
+    pub fn get(self: &Rc<Self>, name: &Token) -> Result<Object, SyntaxError> {
+        if let Some(value) = self.fields.borrow().get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        if let Some(method) = self.class.find_method(&name.lexeme) {
+            return Ok(Object::Func(Rc::new(method.bind(self))));
+        }
+
+        Err(SyntaxError::new(
+            name.line,
+            name.position,
+            SyntaxErrorTypes::UndefinedProperty(name.lexeme.clone(), self.class.name().to_string()),
+        ))
+    }
+
+    pub fn class_name(&self) -> &str {
+        self.class.name()
+    }
+
+    pub fn set(&self, name: &Token, value: Object) {
+        self.fields.borrow_mut().insert(name.lexeme.clone(), value);
+    }
+}
+
+impl fmt::Display for CedarInstance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{} instance>", self.class.name())
+    }
+}
+
+impl fmt::Debug for CedarInstance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Instances compare by identity.
+impl PartialEq for CedarInstance {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
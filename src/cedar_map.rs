@@ -0,0 +1,98 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::errors::syntax_error::SyntaxErrorTypes;
+use crate::object::*;
+
+/// The subset of values that can be used as map keys. Floats are left
+/// out on purpose: `0.1 + 0.2` and `0.3` should not silently be two
+/// different keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+impl MapKey {
+    pub fn from_object(value: &Object) -> Result<MapKey, SyntaxErrorTypes> {
+        match value {
+            Object::Str(x) => Ok(MapKey::Str(x.clone())),
+            Object::Int(x) => Ok(MapKey::Int(*x)),
+            Object::Bool(x) => Ok(MapKey::Bool(*x)),
+            other => Err(SyntaxErrorTypes::UnhashableKey(other.type_name())),
+        }
+    }
+
+    pub fn to_object(&self) -> Object {
+        match self {
+            MapKey::Str(x) => Object::Str(x.clone()),
+            MapKey::Int(x) => Object::Int(*x),
+            MapKey::Bool(x) => Object::Bool(*x),
+        }
+    }
+}
+
+/// A map that remembers the order keys were first inserted in, so
+/// printing a map or walking its keys is deterministic. Overwriting an
+/// existing key keeps its original position.
+#[derive(Debug, Default)]
+pub struct CedarMap {
+    entries: Vec<(MapKey, Object)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl CedarMap {
+    pub fn new() -> CedarMap {
+        CedarMap::default()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&Object> {
+        self.positions.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    pub fn insert(&mut self, key: MapKey, value: Object) {
+        match self.positions.get(&key) {
+            Some(&i) => self.entries[i].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &MapKey> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Object> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+impl fmt::Display for CedarMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key.to_object().repr(), value.repr()))
+            .collect();
+        write!(f, "{{{}}}", entries.join(", "))
+    }
+}
+
+/// Maps are equal when they hold the same keys with equal values,
+/// regardless of insertion order.
+impl PartialEq for CedarMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::errors::syntax_error::{SyntaxError, SyntaxErrorTypes};
+use crate::object::*;
+use crate::tokens::token::*;
+
+pub struct Environment {
+    values: HashMap<String, Object>,
+    /// Names declared with `have`, mapped to their declaring token. The
+    /// resolver rejects most writes to constants, but not one from a
+    /// function resolved before the global constant was declared.
+    constants: HashMap<String, Token>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Environment {
+        Environment {
+            values: HashMap::new(),
+            constants: HashMap::new(),
+            enclosing: None,
+        }
+    }
+
+    /// Creates a child scope. Lookups and assignments that miss in the
+    /// child fall through to the enclosing environment.
+    pub fn new_enclosing(enclosing: Rc<RefCell<Environment>>) -> Environment {
+        Environment {
+            values: HashMap::new(),
+            constants: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    /// Binds a name to a value in this scope. Declaring a name that
+    /// already exists simply overwrites the previous value, which keeps
+    /// redeclaration at the prompt painless.
+    pub fn define(&mut self, name: &str, value: Object) {
+        self.constants.remove(name);
+        self.values.insert(name.to_string(), value);
+    }
+
+    /// Binds a `have` constant, which `assign` will refuse to change.
+    pub fn define_constant(&mut self, name: &Token, value: Object) {
+        self.values.insert(name.lexeme.clone(), value);
+        self.constants.insert(name.lexeme.clone(), name.clone());
+    }
+
+    /// Looks up the value bound to the token's lexeme, walking outwards
+    /// through the enclosing scopes. If the name was never declared, an
+    /// UndefinedVariable error located at the token is returned.
+    pub fn get(&self, name: &Token) -> Result<Object, SyntaxError> {
+        if let Some(object) = self.values.get(&name.lexeme) {
+            return Ok(object.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(SyntaxError::new(
+                name.line,
+                name.position,
+                SyntaxErrorTypes::UndefinedVariable(name.lexeme.clone()),
+            )),
+        }
+    }
+
+    /// Reads a binding from this scope only, without walking outwards.
+    pub fn get_local(&self, name: &str) -> Option<Object> {
+        self.values.get(name).cloned()
+    }
+
+    /// Replaces the value of an existing binding. Assignment never
+    /// creates a variable, so assigning to an undeclared name is an
+    /// UndefinedVariable error, and assigning to a constant is a
+    /// ConstantChanged error. The resolver catches most of those before
+    /// anything runs; this is for a function resolved before the global
+    /// constant it writes to was declared.
+    pub fn assign(&mut self, name: &Token, value: Object) -> Result<(), SyntaxError> {
+        if let Some(declaration) = self.constants.get(&name.lexeme) {
+            return Err(SyntaxError::new(
+                name.line,
+                name.position,
+                SyntaxErrorTypes::ConstantChanged(
+                    name.lexeme.clone(),
+                    declaration.line,
+                    declaration.position,
+                ),
+            ));
+        }
+        if let Some(slot) = self.values.get_mut(&name.lexeme) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(SyntaxError::new(
+                name.line,
+                name.position,
+                SyntaxErrorTypes::UndefinedVariable(name.lexeme.clone()),
+            )),
+        }
+    }
+
+    /// Reads a name from the environment exactly `distance` hops out.
+    /// The resolver already proved the binding lives there, so no
+    /// searching happens on the way.
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Object, SyntaxError> {
+        if distance == 0 {
+            return self.get(name);
+        }
+        self.enclosing
+            .as_ref()
+            .expect("resolved scope depth is deeper than the environment chain")
+            .borrow()
+            .get_at(distance - 1, name)
+    }
+
+    pub fn assign_at(
+        &mut self,
+        distance: usize,
+        name: &Token,
+        value: Object,
+    ) -> Result<(), SyntaxError> {
+        if distance == 0 {
+            return self.assign(name, value);
+        }
+        self.enclosing
+            .as_ref()
+            .expect("resolved scope depth is deeper than the environment chain")
+            .borrow_mut()
+            .assign_at(distance - 1, name, value)
+    }
+}
@@ -1,4 +1,5 @@
 use ansi_term::Colour::{Blue, Purple, Red, Yellow};
+use unicode_width::UnicodeWidthChar;
 
 #[derive(Debug, Clone)]
 pub struct LexerError {
@@ -14,8 +15,9 @@ pub struct LexerError {
 pub enum LexerErrorTypes {
     UnexpectedEndOfString,
     UnexpectedCharacter(char),
-    InvalidFloatingPoint(),
     InvalidNumber(String),
+    InvalidEscape(String),
+    UnterminatedComment,
 }
 
 impl LexerError {
@@ -33,10 +35,13 @@ impl LexerError {
             LexerErrorTypes::UnexpectedCharacter(c) => {
                 format!("error[E2503] Unexpected character '{}'", c)
             }
-            LexerErrorTypes::InvalidFloatingPoint() => {
-                format!("error[E2504] Invalid floating point.")
+            LexerErrorTypes::InvalidNumber(_) => "error[E2501] Invalid number".to_string(),
+            LexerErrorTypes::InvalidEscape(s) => {
+                format!("error[E2505] Invalid escape sequence '{}'", s)
+            }
+            LexerErrorTypes::UnterminatedComment => {
+                "error[E2506] Unterminated block comment".to_string()
             }
-            LexerErrorTypes::InvalidNumber(s) => format!("Invalid number '{}'", s),
         };
         LexerError {
             error_type,
@@ -48,16 +53,21 @@ impl LexerError {
         }
     }
     fn message(&self) -> String {
-        return match &self.error_type {
+        match &self.error_type {
             LexerErrorTypes::UnexpectedEndOfString => "Unexpected end of string".to_string(),
             LexerErrorTypes::UnexpectedCharacter(c) => {
                 format!("Tip: Try to remove or replace this character: {}.", c)
             }
-            LexerErrorTypes::InvalidFloatingPoint() => {
-                format!("Floating point should have a digit after it. \n ")
+            LexerErrorTypes::InvalidNumber(problem) => problem.to_string(),
+            LexerErrorTypes::UnterminatedComment => {
+                "Tip: Close this comment with */. Block comments nest, so each /* needs its own */."
+                    .to_string()
             }
-            LexerErrorTypes::InvalidNumber(s) => format!("Invalid number '{}'", s),
-        };
+            LexerErrorTypes::InvalidEscape(_) => {
+                "Tip: Valid escapes are \\n \\t \\r \\0 \\\\ \\\" \\' \\$ and \\u{XXXX}."
+                    .to_string()
+            }
+        }
     }
     // [src/test.ql->1:20::Unexpected token found. of type: ;
     // 0 |
@@ -67,80 +77,72 @@ impl LexerError {
 
     ///reports an error by displaying the relevant code line,
     /// error message, and error location. It also exits the
-    /// program with a status code of 64.
-    pub fn report(self) {
-        let mut line: String = "".to_string();
-
-        for token in self.source_toks.clone() {
-            line = format!("{}{}", line, token.to_string());
-        }
-
+    /// program with a status code of 64. `tab_width` must match the
+    /// one the scanner counted columns with, so the caret lines up under
+    /// tabs in the quoted line.
+    pub fn report(self, tab_width: usize) {
         // TheDevConnor: 2023-04-08 12:00:00
         // I was able toi find a small bug. the error line was returning the wrong line number.
         // I fixed it by adding 1 to the line number.
         // The reason for this is because the line number is 0 indexed and the error line function is 1 indexed.
         // Hence we need to do the self.line + 1 to get the correct line number.
-        let error_line: String = get_error_line(&self.source_toks, self.line).to_string();
-        let mut error_message: String = "".to_string();
-        if self.line < 10 {
-            error_message = format!(
-                "{}\n    {}\n",
-                format!("{}", error_line),
-                format!(
-                    "{} {}",
-                    Red.bold().paint(flash_error_location(self.column + 2,)),
-                    Red.bold().paint(self.message())
-                ),
-            )
-        }
+        let gutter = format!("{:02} | ", self.line + 1);
+        let error_line = get_error_line(&self.source_toks, self.line, tab_width);
+        let error_message = format!(
+            "{}{}\n{} {}\n",
+            gutter,
+            error_line,
+            Red.bold()
+                .paint(flash_error_location(gutter.len() + self.column + 1)),
+            Red.bold().paint(self.message())
+        );
 
         eprint!(
-            "\n\n[{}] | [{}]:=> {}\n\n{}",
+            "\n\n[{}] | [{}:{}]:=> {}\n{}\n\n{}",
             Yellow.bold().paint(self.file_name),
-            format!(
-                "{}:{}",
-                Red.bold().paint((self.line + 1).to_string()),
-                Purple.bold().paint(self.column.to_string())
-            ),
-            format!(
-                "{}\n{}",
-                Red.bold().paint("Exception Occured"),
-                Blue.bold().paint(self.main_message),
-            ),
+            Red.bold().paint((self.line + 1).to_string()),
+            Purple.bold().paint(self.column.to_string()),
+            Red.bold().paint("Exception Occured"),
+            Blue.bold().paint(self.main_message),
             error_message
         );
         std::process::exit(64);
     }
 }
 
-/// takes a vector of characters source_toks and a line number line_num,
-/// and returns a formatted string representing the line of the given
-/// line number. It first finds the start and end indices of the line
-/// in the source_toks vector, then creates a substring of the line by
-/// collecting the characters between those indices into a String. Finally,
-/// it formats and returns the line as a string with the line number and a
-/// leading zero followed by a vertical bar (|) separator.
-fn get_error_line(source_toks: &[char], line_num: usize) -> String {
-    let line_start = source_toks
-        .iter()
-        .enumerate()
-        .find(|(_, &c)| c == '\n')
-        .map(|(i, _)| i + 1)
-        .unwrap_or(0);
-    let line_end = source_toks
-        .iter()
-        .skip(line_start)
-        .position(|&c| c == '\n')
-        .map(|i| i + line_start)
-        .unwrap_or(source_toks.len());
-    let line = source_toks[line_start..line_end].iter().collect::<String>();
-    format!("0{} | {}", line_num + 1, line)
+/// Returns the source line with the given 0-based number, with tabs
+/// expanded to the next multiple of `tab_width` the same way the scanner
+/// counts columns. Lines end at `\n`, `\r\n` or a lone `\r`.
+fn get_error_line(source_toks: &[char], line_num: usize, tab_width: usize) -> String {
+    let source: String = source_toks.iter().collect();
+    let line = source
+        .split('\n')
+        .flat_map(|line| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            line.split('\r')
+        })
+        .nth(line_num)
+        .unwrap_or("");
+
+    // Tab stops depend on the display width so far, not the number of
+    // characters, or a wide character before a tab shifts the caret.
+    let mut expanded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let stop = tab_width - width % tab_width;
+            expanded.extend(std::iter::repeat_n(' ', stop));
+            width += stop;
+        } else if c != '\u{FEFF}' {
+            expanded.push(c);
+            width += c.width().unwrap_or(0);
+        }
+    }
+    expanded
 }
 
 fn flash_error_location(col_num: usize) -> String {
-    let pointer = std::iter::repeat(' ')
-        .take(col_num - 1)
+    std::iter::repeat_n(' ', col_num - 1)
         .chain(std::iter::once('^'))
-        .collect::<String>();
-    format!("{}", pointer)
+        .collect::<String>()
 }
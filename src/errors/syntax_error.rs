@@ -1,3 +1,7 @@
+use crate::object::*;
+use crate::tokens::token::*;
+use crate::tokens::token_type::*;
+
 pub struct SyntaxError {
     error_type: SyntaxErrorTypes,
     line: usize,
@@ -8,6 +12,18 @@ pub struct SyntaxError {
 pub enum SyntaxErrorTypes {
     UnexpectedToken(String),
     ExpectedToken(String, String),
+    InvalidAssignmentTarget(),
+    AlreadyDeclared(String),
+    ReadInOwnInitializer(String),
+    AssignToConstant(String, usize, usize),
+    TopLevelReturn(),
+    ThisOutsideClass(),
+    InheritFromSelf(String),
+    SuperOutsideClass(),
+    SuperWithoutSuperclass(),
+    ReturnFromInitializer(),
+    OutsideLoop(String),
+    UndefinedLabel(String),
     // UnexpectedCharacter(char),
     // UnclosedDelimiter(char),
     // MissingOperand(String),
@@ -15,28 +31,135 @@ pub enum SyntaxErrorTypes {
     // InvalidSyntax(String),
     OperandNaN(),
     InvalidUnary(),
+    InvalidArithmetic(String),
+    UndefinedVariable(String),
+    ConstantChanged(String, usize, usize),
+    NotCallable(),
+    OnlyInstancesHaveProperties(),
+    SuperclassNotClass(),
+    InvalidArgument(String, String, String),
+    NotIndexable(String),
+    IndexNotInt(String),
+    IndexOutOfRange(i64, usize),
+    UnhashableKey(String),
+    KeyNotFound(String),
+    NumberConversion(String),
+    UndefinedProperty(String, String),
+    ArityMismatch(usize, usize),
+    StackOverflow(usize),
+    ReturnValue(Object),
+    Break(Option<String>),
+    Continue(Option<String>),
+    ShortCircuit(),
+}
+
+/// What a loop does with a `break` or `continue` it has caught.
+pub enum LoopControl {
+    Break,
+    Continue,
 }
 
 impl SyntaxError {
     pub fn new(line: usize, column: usize, error_type: SyntaxErrorTypes) -> SyntaxError {
         let main_message = match &error_type {
             SyntaxErrorTypes::UnexpectedToken(c) => {
-                format!("{}: Token \"{}\"", "Unexpected Token".to_string(), c)
+                format!("{}: Token \"{}\"", "Unexpected Token", c)
             }
             SyntaxErrorTypes::ExpectedToken(c, d) => {
                 format!("Expected Token \"{}\", got \"{}\".", c, d)
             }
-            SyntaxErrorTypes::OperandNaN() => {
-                format!("Operand must be a number.")
+            SyntaxErrorTypes::InvalidAssignmentTarget() => "Invalid assignment target.".to_string(),
+            SyntaxErrorTypes::AlreadyDeclared(name) => {
+                format!("Variable \"{}\" is already declared in this scope.", name)
+            }
+            SyntaxErrorTypes::AssignToConstant(name, line, column)
+            | SyntaxErrorTypes::ConstantChanged(name, line, column) => format!(
+                "Cannot assign to constant \"{}\" declared with have at [{}:{}].",
+                name,
+                line + 1,
+                column
+            ),
+            SyntaxErrorTypes::ReadInOwnInitializer(name) => {
+                format!(
+                    "Cannot read local variable \"{}\" in its own initializer.",
+                    name
+                )
+            }
+            SyntaxErrorTypes::TopLevelReturn() => "Cannot return from top-level code.".to_string(),
+            SyntaxErrorTypes::ThisOutsideClass() => {
+                "Cannot use \"this\" outside of a class.".to_string()
+            }
+            SyntaxErrorTypes::InheritFromSelf(name) => {
+                format!("Class \"{}\" cannot inherit from itself.", name)
             }
-            SyntaxErrorTypes::InvalidUnary() => {
-                format!("Invalid unary expression.")
+            SyntaxErrorTypes::SuperOutsideClass() => {
+                "Cannot use \"super\" outside of a class.".to_string()
             }
+            SyntaxErrorTypes::SuperWithoutSuperclass() => {
+                "Cannot use \"super\" in a class with no superclass.".to_string()
+            }
+            SyntaxErrorTypes::ReturnFromInitializer() => {
+                "Cannot return a value from an initializer.".to_string()
+            }
+            SyntaxErrorTypes::OutsideLoop(keyword) => {
+                format!("Cannot use \"{}\" outside of a loop.", keyword)
+            }
+            SyntaxErrorTypes::UndefinedLabel(label) => {
+                format!("No enclosing loop is labelled \"{}\".", label)
+            }
+            SyntaxErrorTypes::OperandNaN() => "Operand must be a number.".to_string(),
+            SyntaxErrorTypes::InvalidUnary() => "Invalid unary expression.".to_string(),
+            SyntaxErrorTypes::InvalidArithmetic(operator) => format!(
+                "Invalid operands for \"{}\": mismatched types, overflow or division by zero.",
+                operator
+            ),
             // SyntaxErrorTypes::UnexpectedCharacter(_) => todo!(),
             // SyntaxErrorTypes::UnclosedDelimiter(_) => todo!(),
             // SyntaxErrorTypes::MissingOperand(_) => todo!(),
             // SyntaxErrorTypes::RedundantOperand(_) => todo!(),
             // SyntaxErrorTypes::InvalidSyntax(_) => todo!(),
+            SyntaxErrorTypes::UndefinedVariable(name) => {
+                format!("Undefined variable \"{}\".", name)
+            }
+            SyntaxErrorTypes::NotCallable() => "Can only call functions and classes.".to_string(),
+            SyntaxErrorTypes::OnlyInstancesHaveProperties() => {
+                "Only instances have properties.".to_string()
+            }
+            SyntaxErrorTypes::SuperclassNotClass() => "Superclass must be a class.".to_string(),
+            SyntaxErrorTypes::InvalidArgument(function, expected, got) => {
+                format!("{}() expects {} but got {}.", function, expected, got)
+            }
+            SyntaxErrorTypes::NotIndexable(type_name) => {
+                format!("Cannot index into a value of type {}.", type_name)
+            }
+            SyntaxErrorTypes::IndexNotInt(type_name) => {
+                format!("Index must be an int, got {}.", type_name)
+            }
+            SyntaxErrorTypes::IndexOutOfRange(index, len) => {
+                format!("Index {} is out of range for length {}.", index, len)
+            }
+            SyntaxErrorTypes::UnhashableKey(type_name) => {
+                format!("A value of type {} cannot be used as a map key.", type_name)
+            }
+            SyntaxErrorTypes::KeyNotFound(key) => format!("Key {} not found in map.", key),
+            SyntaxErrorTypes::NumberConversion(s) => {
+                format!("Cannot convert \"{}\" to a number.", s)
+            }
+            SyntaxErrorTypes::UndefinedProperty(name, class) => {
+                format!("Undefined property \"{}\" on {} instance.", name, class)
+            }
+            SyntaxErrorTypes::ArityMismatch(expected, got) => {
+                format!("Expected {} arguments but got {}.", expected, got)
+            }
+            SyntaxErrorTypes::StackOverflow(limit) => {
+                format!("Stack overflow: more than {} nested calls.", limit)
+            }
+            SyntaxErrorTypes::ReturnValue(_) => "Cannot return from top-level code.".to_string(),
+            SyntaxErrorTypes::Break(_) => "Cannot use \"break\" outside of a loop.".to_string(),
+            SyntaxErrorTypes::Continue(_) => {
+                "Cannot use \"continue\" outside of a loop.".to_string()
+            }
+            SyntaxErrorTypes::ShortCircuit() => "Cannot use \"?.\" outside of a chain.".to_string(),
         };
         SyntaxError {
             error_type,
@@ -46,6 +169,112 @@ impl SyntaxError {
         }
     }
 
+    /// Wraps the value of a `ret` statement so it can unwind through
+    /// the interpreter like an error until the enclosing call catches it.
+    /// It is located at the keyword in case nothing does.
+    pub fn return_value(keyword: &Token, value: Object) -> SyntaxError {
+        SyntaxError::new(
+            keyword.line,
+            keyword.position,
+            SyntaxErrorTypes::ReturnValue(value),
+        )
+    }
+
+    /// Unwraps a value produced by `return_value`, handing any other
+    /// error back untouched so it keeps propagating.
+    pub fn take_return_value(self) -> Result<Object, SyntaxError> {
+        match self.error_type {
+            SyntaxErrorTypes::ReturnValue(value) => Ok(value),
+            _ => Err(self),
+        }
+    }
+
+    /// Wraps a `break` or `continue` so it can unwind to its loop the
+    /// same way `return_value` unwinds to its call.
+    pub fn loop_control(keyword: &Token, label: &Option<Token>) -> SyntaxError {
+        let label = label.as_ref().map(|label| label.lexeme.clone());
+        let error_type = match keyword.ttype {
+            TokenType::BREAK => SyntaxErrorTypes::Break(label),
+            _ => SyntaxErrorTypes::Continue(label),
+        };
+        SyntaxError::new(keyword.line, keyword.position, error_type)
+    }
+
+    /// Claims a `break` or `continue` meant for the loop with the given
+    /// label. An unlabelled one is meant for the innermost loop; one
+    /// naming an outer loop, and any other error, is handed back to keep
+    /// unwinding.
+    pub fn take_loop_control(self, label: &Option<Token>) -> Result<LoopControl, SyntaxError> {
+        let (control, target) = match &self.error_type {
+            SyntaxErrorTypes::Break(target) => (LoopControl::Break, target),
+            SyntaxErrorTypes::Continue(target) => (LoopControl::Continue, target),
+            _ => return Err(self),
+        };
+        match (target, label) {
+            (None, _) => Ok(control),
+            (Some(target), Some(label)) if *target == label.lexeme => Ok(control),
+            _ => Err(self),
+        }
+    }
+
+    /// Signals that a `?.` found null, so the rest of its chain is
+    /// skipped. The enclosing SafeChain catches it.
+    pub fn short_circuit(token: &Token) -> SyntaxError {
+        SyntaxError::new(token.line, token.position, SyntaxErrorTypes::ShortCircuit())
+    }
+
+    /// Absorbs a signal produced by `short_circuit`, handing any other
+    /// error back untouched.
+    pub fn take_short_circuit(self) -> Result<(), SyntaxError> {
+        match self.error_type {
+            SyntaxErrorTypes::ShortCircuit() => Ok(()),
+            _ => Err(self),
+        }
+    }
+
+    /// Errors raised while evaluating are reported as runtime errors,
+    /// everything the parser produces is reported as a syntax error.
+    fn kind(&self) -> &'static str {
+        match self.error_type {
+            SyntaxErrorTypes::UnexpectedToken(_)
+            | SyntaxErrorTypes::ExpectedToken(_, _)
+            | SyntaxErrorTypes::InvalidAssignmentTarget()
+            | SyntaxErrorTypes::AlreadyDeclared(_)
+            | SyntaxErrorTypes::ReadInOwnInitializer(_)
+            | SyntaxErrorTypes::AssignToConstant(_, _, _)
+            | SyntaxErrorTypes::TopLevelReturn()
+            | SyntaxErrorTypes::ThisOutsideClass()
+            | SyntaxErrorTypes::InheritFromSelf(_)
+            | SyntaxErrorTypes::SuperOutsideClass()
+            | SyntaxErrorTypes::SuperWithoutSuperclass()
+            | SyntaxErrorTypes::ReturnFromInitializer()
+            | SyntaxErrorTypes::OutsideLoop(_)
+            | SyntaxErrorTypes::UndefinedLabel(_) => "Syntax Error",
+            SyntaxErrorTypes::OperandNaN()
+            | SyntaxErrorTypes::InvalidUnary()
+            | SyntaxErrorTypes::InvalidArithmetic(_)
+            | SyntaxErrorTypes::UndefinedVariable(_)
+            | SyntaxErrorTypes::ConstantChanged(_, _, _)
+            | SyntaxErrorTypes::NotCallable()
+            | SyntaxErrorTypes::OnlyInstancesHaveProperties()
+            | SyntaxErrorTypes::SuperclassNotClass()
+            | SyntaxErrorTypes::InvalidArgument(_, _, _)
+            | SyntaxErrorTypes::NotIndexable(_)
+            | SyntaxErrorTypes::IndexNotInt(_)
+            | SyntaxErrorTypes::IndexOutOfRange(_, _)
+            | SyntaxErrorTypes::UnhashableKey(_)
+            | SyntaxErrorTypes::KeyNotFound(_)
+            | SyntaxErrorTypes::NumberConversion(_)
+            | SyntaxErrorTypes::UndefinedProperty(_, _)
+            | SyntaxErrorTypes::ArityMismatch(_, _)
+            | SyntaxErrorTypes::StackOverflow(_)
+            | SyntaxErrorTypes::ReturnValue(_)
+            | SyntaxErrorTypes::Break(_)
+            | SyntaxErrorTypes::Continue(_)
+            | SyntaxErrorTypes::ShortCircuit() => "Runtime Error",
+        }
+    }
+
     // [src/test.ql->1:20::Unexpected token found. of type: ;
     // 0 |
     // 1 | have shipSpeedX := 0;
@@ -53,12 +282,20 @@ impl SyntaxError {
     // 2 |
     pub fn report(self) {
         eprint!(
-            "[{}:{}] Syntax Error :=> {}",
+            "[{}:{}] {} :=> {}",
             self.line + 1,
             self.column,
+            self.kind(),
             self.main_message
         );
-        std::process::exit(64);
+        // Runtime errors exit like sysexits' EX_SOFTWARE, so a script
+        // that failed part way can be told apart from one that never ran.
+        let code = if self.kind() == "Runtime Error" {
+            70
+        } else {
+            64
+        };
+        std::process::exit(code);
     }
 }
 
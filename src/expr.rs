@@ -1,83 +1,335 @@
-use crate::error::*;
 use crate::tokens::token::*;
 use crate::object::*;
 use crate::errors::syntax_error::*;
+use std::cell::Cell;
 
 pub enum Expr {
+    Assign(AssignExpr),
     Binary(BinaryExpr),
+    Call(CallExpr),
+    Compound(CompoundExpr),
+    Conditional(ConditionalExpr),
+    Get(GetExpr),
     Grouping(GroupingExpr),
+    Index(IndexExpr),
+    Interpolation(InterpolationExpr),
+    List(ListExpr),
     Literal(LiteralExpr),
+    Logical(LogicalExpr),
+    Map(MapExpr),
+    SafeChain(SafeChainExpr),
+    Set(SetExpr),
+    SetIndex(SetIndexExpr),
+    Slice(SliceExpr),
+    Super(SuperExpr),
+    This(ThisExpr),
     Unary(UnaryExpr),
+    Update(UpdateExpr),
     Variable(VariableExpr),
 }
 
 impl Expr {
     pub fn accept<T>(&self, expr_visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         match self {
+            Expr::Assign(v) => v.accept(expr_visitor),
             Expr::Binary(v) => v.accept(expr_visitor),
+            Expr::Call(v) => v.accept(expr_visitor),
+            Expr::Compound(v) => v.accept(expr_visitor),
+            Expr::Conditional(v) => v.accept(expr_visitor),
+            Expr::Get(v) => v.accept(expr_visitor),
             Expr::Grouping(v) => v.accept(expr_visitor),
+            Expr::Index(v) => v.accept(expr_visitor),
+            Expr::Interpolation(v) => v.accept(expr_visitor),
+            Expr::List(v) => v.accept(expr_visitor),
             Expr::Literal(v) => v.accept(expr_visitor),
+            Expr::Logical(v) => v.accept(expr_visitor),
+            Expr::Map(v) => v.accept(expr_visitor),
+            Expr::SafeChain(v) => v.accept(expr_visitor),
+            Expr::Set(v) => v.accept(expr_visitor),
+            Expr::SetIndex(v) => v.accept(expr_visitor),
+            Expr::Slice(v) => v.accept(expr_visitor),
+            Expr::Super(v) => v.accept(expr_visitor),
+            Expr::This(v) => v.accept(expr_visitor),
             Expr::Unary(v) => v.accept(expr_visitor),
+            Expr::Update(v) => v.accept(expr_visitor),
             Expr::Variable(v) => v.accept(expr_visitor),
         }
     }
 }
 
+pub struct AssignExpr {
+    pub name: Token,
+    pub value: Box<Expr>,
+    pub depth: Cell<Option<usize>>,
+}
+
 pub struct BinaryExpr {
     pub left: Box<Expr>,
     pub operator: Token,
     pub right: Box<Expr>,
 }
 
+pub struct CallExpr {
+    pub callee: Box<Expr>,
+    pub paren: Token,
+    pub arguments: Vec<Expr>,
+    pub safe: bool,
+}
+
+pub struct CompoundExpr {
+    pub target: Box<Expr>,
+    pub operator: Token,
+    pub value: Box<Expr>,
+}
+
+pub struct ConditionalExpr {
+    pub condition: Box<Expr>,
+    pub then_branch: Box<Expr>,
+    pub else_branch: Box<Expr>,
+}
+
+pub struct GetExpr {
+    pub object: Box<Expr>,
+    pub name: Token,
+    pub safe: bool,
+}
+
 pub struct GroupingExpr {
     pub expression: Box<Expr>,
 }
 
+pub struct IndexExpr {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+}
+
+pub struct InterpolationExpr {
+    pub parts: Vec<Expr>,
+}
+
+pub struct ListExpr {
+    pub elements: Vec<Expr>,
+}
+
 pub struct LiteralExpr {
     pub value: Option<Object>,
 }
 
+pub struct LogicalExpr {
+    pub left: Box<Expr>,
+    pub operator: Token,
+    pub right: Box<Expr>,
+}
+
+pub struct MapExpr {
+    pub brace: Token,
+    pub keys: Vec<Expr>,
+    pub values: Vec<Expr>,
+}
+
+pub struct SafeChainExpr {
+    pub expression: Box<Expr>,
+}
+
+pub struct SetExpr {
+    pub object: Box<Expr>,
+    pub name: Token,
+    pub value: Box<Expr>,
+}
+
+pub struct SetIndexExpr {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+pub struct SliceExpr {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub start: Option<Box<Expr>>,
+    pub end: Option<Box<Expr>>,
+}
+
+pub struct SuperExpr {
+    pub keyword: Token,
+    pub method: Token,
+    pub depth: Cell<Option<usize>>,
+}
+
+pub struct ThisExpr {
+    pub keyword: Token,
+    pub depth: Cell<Option<usize>>,
+}
+
 pub struct UnaryExpr {
     pub operator: Token,
     pub right: Box<Expr>,
 }
 
+pub struct UpdateExpr {
+    pub target: Box<Expr>,
+    pub operator: Token,
+    pub prefix: bool,
+}
+
 pub struct VariableExpr {
     pub name: Token,
+    pub depth: Cell<Option<usize>>,
 }
 
 pub trait ExprVisitor<T> {
+    fn visit_assign_expr(&self, expr: &AssignExpr) -> Result<T, SyntaxError>;
     fn visit_binary_expr(&self, expr: &BinaryExpr) -> Result<T, SyntaxError>;
+    fn visit_call_expr(&self, expr: &CallExpr) -> Result<T, SyntaxError>;
+    fn visit_compound_expr(&self, expr: &CompoundExpr) -> Result<T, SyntaxError>;
+    fn visit_conditional_expr(&self, expr: &ConditionalExpr) -> Result<T, SyntaxError>;
+    fn visit_get_expr(&self, expr: &GetExpr) -> Result<T, SyntaxError>;
     fn visit_grouping_expr(&self, expr: &GroupingExpr) -> Result<T, SyntaxError>;
+    fn visit_index_expr(&self, expr: &IndexExpr) -> Result<T, SyntaxError>;
+    fn visit_interpolation_expr(&self, expr: &InterpolationExpr) -> Result<T, SyntaxError>;
+    fn visit_list_expr(&self, expr: &ListExpr) -> Result<T, SyntaxError>;
     fn visit_literal_expr(&self, expr: &LiteralExpr) -> Result<T, SyntaxError>;
+    fn visit_logical_expr(&self, expr: &LogicalExpr) -> Result<T, SyntaxError>;
+    fn visit_map_expr(&self, expr: &MapExpr) -> Result<T, SyntaxError>;
+    fn visit_safechain_expr(&self, expr: &SafeChainExpr) -> Result<T, SyntaxError>;
+    fn visit_set_expr(&self, expr: &SetExpr) -> Result<T, SyntaxError>;
+    fn visit_setindex_expr(&self, expr: &SetIndexExpr) -> Result<T, SyntaxError>;
+    fn visit_slice_expr(&self, expr: &SliceExpr) -> Result<T, SyntaxError>;
+    fn visit_super_expr(&self, expr: &SuperExpr) -> Result<T, SyntaxError>;
+    fn visit_this_expr(&self, expr: &ThisExpr) -> Result<T, SyntaxError>;
     fn visit_unary_expr(&self, expr: &UnaryExpr) -> Result<T, SyntaxError>;
+    fn visit_update_expr(&self, expr: &UpdateExpr) -> Result<T, SyntaxError>;
     fn visit_variable_expr(&self, expr: &VariableExpr) -> Result<T, SyntaxError>;
 }
 
+impl AssignExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_assign_expr(self)
+    }
+}
+
 impl BinaryExpr {
     pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         visitor.visit_binary_expr(self)
     }
 }
 
+impl CallExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_call_expr(self)
+    }
+}
+
+impl CompoundExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_compound_expr(self)
+    }
+}
+
+impl ConditionalExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_conditional_expr(self)
+    }
+}
+
+impl GetExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_get_expr(self)
+    }
+}
+
 impl GroupingExpr {
     pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         visitor.visit_grouping_expr(self)
     }
 }
 
+impl IndexExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_index_expr(self)
+    }
+}
+
+impl InterpolationExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_interpolation_expr(self)
+    }
+}
+
+impl ListExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_list_expr(self)
+    }
+}
+
 impl LiteralExpr {
     pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         visitor.visit_literal_expr(self)
     }
 }
 
+impl LogicalExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_logical_expr(self)
+    }
+}
+
+impl MapExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_map_expr(self)
+    }
+}
+
+impl SafeChainExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_safechain_expr(self)
+    }
+}
+
+impl SetExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_set_expr(self)
+    }
+}
+
+impl SetIndexExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_setindex_expr(self)
+    }
+}
+
+impl SliceExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_slice_expr(self)
+    }
+}
+
+impl SuperExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_super_expr(self)
+    }
+}
+
+impl ThisExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_this_expr(self)
+    }
+}
+
 impl UnaryExpr {
     pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         visitor.visit_unary_expr(self)
     }
 }
 
+impl UpdateExpr {
+    pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
+        visitor.visit_update_expr(self)
+    }
+}
+
 impl VariableExpr {
     pub fn accept<T>(&self, visitor: &dyn ExprVisitor<T>) -> Result<T, SyntaxError> {
         visitor.visit_variable_expr(self)
@@ -1,28 +1,83 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use num_traits::Pow;
+use unicode_segmentation::UnicodeSegmentation;
 
+use crate::callable::*;
+use crate::cedar_class::*;
+use crate::cedar_function::*;
+use crate::environment::*;
+use crate::errors::syntax_error::LoopControl;
 use crate::errors::syntax_error::SyntaxError;
 use crate::errors::syntax_error::SyntaxErrorTypes;
 use crate::expr::*;
 
+use crate::cedar_map::*;
+use crate::native_function::*;
+use crate::natives;
 use crate::object::*;
 
+use crate::stmt::BlockStmt;
+use crate::stmt::BreakStmt;
+use crate::stmt::ClassStmt;
+use crate::stmt::ContinueStmt;
 use crate::stmt::ExpressionStmt;
+use crate::stmt::FunctionStmt;
+use crate::stmt::IfStmt;
 use crate::stmt::PrintStmt;
+use crate::stmt::ReturnStmt;
 use crate::stmt::Stmt;
 use crate::stmt::StmtVisitor;
 use crate::stmt::VarStmt;
+use crate::stmt::WhileStmt;
 use crate::tokens::token::*;
 use crate::tokens::token_type::*;
 
-pub struct Interpreter {}
+/// How many calls may be in progress at once. Each one takes several
+/// Rust stack frames, so deeper recursion in a script is reported as a
+/// stack overflow rather than overflowing the interpreter's own stack.
+const MAX_CALL_DEPTH: usize = 1000;
+
+pub struct Interpreter {
+    pub globals: Rc<RefCell<Environment>>,
+    environment: RefCell<Rc<RefCell<Environment>>>,
+    call_depth: Cell<usize>,
+}
 
 impl ExprVisitor<Object> for Interpreter {
     fn visit_literal_expr(&self, expr: &LiteralExpr) -> Result<Object, SyntaxError> {
-        return Ok(expr.value.clone().unwrap());
+        Ok(expr.value.clone().unwrap())
+    }
+
+    /// Logical operators short-circuit and hand back the operand that
+    /// decided the result rather than a coerced boolean.
+    fn visit_logical_expr(&self, expr: &LogicalExpr) -> Result<Object, SyntaxError> {
+        let left = self.evaluate(&expr.left)?;
+
+        let short_circuit = match expr.operator.ttype {
+            TokenType::OR => self.is_truthy(&left),
+            TokenType::QUESTIONQUESTION => !matches!(left, Object::Nil),
+            _ => !self.is_truthy(&left),
+        };
+        if short_circuit {
+            return Ok(left);
+        }
+
+        self.evaluate(&expr.right)
+    }
+
+    fn visit_conditional_expr(&self, expr: &ConditionalExpr) -> Result<Object, SyntaxError> {
+        if self.is_truthy(&self.evaluate(&expr.condition)?) {
+            self.evaluate(&expr.then_branch)
+        } else {
+            self.evaluate(&expr.else_branch)
+        }
     }
 
     fn visit_grouping_expr(&self, expr: &GroupingExpr) -> Result<Object, SyntaxError> {
-        return self.evaluate(&expr.expression);
+        self.evaluate(&expr.expression)
     }
 
     fn visit_unary_expr(&self, expr: &UnaryExpr) -> Result<Object, SyntaxError> {
@@ -40,7 +95,14 @@ impl ExprVisitor<Object> for Interpreter {
                 Interpreter::check_number_operand(expr.operator.clone(), &right)?;
 
                 match right {
-                    Object::Num(num) => Ok(Object::Num(-num)),
+                    Object::Int(num) => num.checked_neg().map(Object::Int).ok_or_else(|| {
+                        SyntaxError::new(
+                            expr.operator.line,
+                            expr.operator.position,
+                            SyntaxErrorTypes::InvalidArithmetic(expr.operator.lexeme.clone()),
+                        )
+                    }),
+                    Object::Float(num) => Ok(Object::Float(-num)),
                     _ => Err(SyntaxError::new(0, 0, SyntaxErrorTypes::OperandNaN())),
                 }
             }
@@ -48,56 +110,443 @@ impl ExprVisitor<Object> for Interpreter {
         }
     }
 
+    /// Prefix `++x` gives the new value, postfix `x++` the old one.
+    fn visit_update_expr(&self, expr: &UpdateExpr) -> Result<Object, SyntaxError> {
+        let operator = match expr.operator.ttype {
+            TokenType::PLUSPLUS => TokenType::PLUS,
+            _ => TokenType::MINUS,
+        };
+
+        let (old, new) = self.modify(&expr.target, |old| {
+            if !matches!(old, Object::Int(_) | Object::Float(_)) {
+                return Err(SyntaxError::new(
+                    expr.operator.line,
+                    expr.operator.position,
+                    SyntaxErrorTypes::OperandNaN(),
+                ));
+            }
+            self.apply_operator(old, operator, Object::Int(1), &expr.operator)
+        })?;
+        Ok(if expr.prefix { new } else { old })
+    }
+
     fn visit_binary_expr(&self, expr: &BinaryExpr) -> Result<Object, SyntaxError> {
         let left = self.evaluate(&expr.left)?;
         let right = self.evaluate(&expr.right)?;
-        let result = match expr.operator.ttype {
-            TokenType::MINUS => left - right,
-            TokenType::SLASH => left / right,
-            TokenType::STAR => left * right,
-            TokenType::PLUS => left + right,
-            TokenType::MODULO => left % right,
-            TokenType::POW => left.pow(right),
-            TokenType::GREATER => Object::Bool(left > right),
-            TokenType::LESS => Object::Bool(left < right),
-            TokenType::GREATEREQUAL => Object::Bool(left >= right),
-            TokenType::LESSEQUAL => Object::Bool(left <= right),
-            TokenType::EQUALEQUAL => Object::Bool(left == right),
-            TokenType::BANGEQUAL => Object::Bool(left != right),
+        self.apply_operator(left, expr.operator.ttype.clone(), right, &expr.operator)
+    }
 
-            _ => Object::ArithmeticError,
+    fn visit_assign_expr(&self, expr: &AssignExpr) -> Result<Object, SyntaxError> {
+        let value = self.evaluate(&expr.value)?;
+        self.assign_variable(&expr.name, expr.depth.get(), value.clone())?;
+        Ok(value)
+    }
+
+    fn visit_call_expr(&self, expr: &CallExpr) -> Result<Object, SyntaxError> {
+        let callee = self.evaluate(&expr.callee)?;
+        // The arguments of `f?.()` are not evaluated when `f` is null.
+        if expr.safe && matches!(callee, Object::Nil) {
+            return Err(SyntaxError::short_circuit(&expr.paren));
+        }
+
+        let mut arguments = Vec::new();
+        for argument in &expr.arguments {
+            arguments.push(self.evaluate(argument)?);
+        }
+
+        let function: Rc<dyn CedarCallable> = match callee {
+            Object::Func(function) => function,
+            Object::Class(class) => class,
+            Object::Native(native) => native,
+            _ => {
+                return Err(SyntaxError::new(
+                    expr.paren.line,
+                    expr.paren.position,
+                    SyntaxErrorTypes::NotCallable(),
+                ))
+            }
         };
-        if result == Object::ArithmeticError {
-            Err(SyntaxError::new(0, 0, SyntaxErrorTypes::InvalidUnary()))
-        } else {
-            Ok(result)
+
+        if arguments.len() != function.arity() {
+            return Err(SyntaxError::new(
+                expr.paren.line,
+                expr.paren.position,
+                SyntaxErrorTypes::ArityMismatch(function.arity(), arguments.len()),
+            ));
+        }
+
+        let depth = self.call_depth.get();
+        if depth >= MAX_CALL_DEPTH {
+            return Err(SyntaxError::new(
+                expr.paren.line,
+                expr.paren.position,
+                SyntaxErrorTypes::StackOverflow(MAX_CALL_DEPTH),
+            ));
+        }
+        self.call_depth.set(depth + 1);
+        let result = function.call(self, arguments, &expr.paren);
+        self.call_depth.set(depth);
+        result
+    }
+
+    /// `x += v` and friends. The target is read and written through
+    /// `modify`, so `xs[f()] += 1` calls `f` once, and the value is
+    /// evaluated after the target.
+    fn visit_compound_expr(&self, expr: &CompoundExpr) -> Result<Object, SyntaxError> {
+        let operator = match expr.operator.ttype {
+            TokenType::PLUSEQUAL => TokenType::PLUS,
+            TokenType::MINUSEQUAL => TokenType::MINUS,
+            TokenType::STAREQUAL => TokenType::STAR,
+            TokenType::SLASHEQUAL => TokenType::SLASH,
+            TokenType::MODULOEQUAL => TokenType::MODULO,
+            _ => TokenType::POW,
+        };
+
+        let (_, new) = self.modify(&expr.target, |old| {
+            let value = self.evaluate(&expr.value)?;
+            self.apply_operator(old, operator, value, &expr.operator)
+        })?;
+        Ok(new)
+    }
+
+    fn visit_interpolation_expr(&self, expr: &InterpolationExpr) -> Result<Object, SyntaxError> {
+        let mut value = String::new();
+        for part in &expr.parts {
+            value.push_str(&self.evaluate(part)?.to_string());
+        }
+        Ok(Object::Str(value))
+    }
+
+    fn visit_list_expr(&self, expr: &ListExpr) -> Result<Object, SyntaxError> {
+        let mut elements = Vec::new();
+        for element in &expr.elements {
+            elements.push(self.evaluate(element)?);
+        }
+        Ok(Object::new_list(elements))
+    }
+
+    fn visit_map_expr(&self, expr: &MapExpr) -> Result<Object, SyntaxError> {
+        let mut map = CedarMap::new();
+        for (key, value) in expr.keys.iter().zip(&expr.values) {
+            let key = Interpreter::map_key(&self.evaluate(key)?, &expr.brace)?;
+            map.insert(key, self.evaluate(value)?);
+        }
+        Ok(Object::new_map(map))
+    }
+
+    fn visit_index_expr(&self, expr: &IndexExpr) -> Result<Object, SyntaxError> {
+        let object = self.evaluate(&expr.object)?;
+        let index = self.evaluate(&expr.index)?;
+        Interpreter::get_index(object, &index, &expr.bracket)
+    }
+
+    fn visit_setindex_expr(&self, expr: &SetIndexExpr) -> Result<Object, SyntaxError> {
+        let object = self.evaluate(&expr.object)?;
+        let index = self.evaluate(&expr.index)?;
+        let value = self.evaluate(&expr.value)?;
+        Interpreter::set_index(object, &index, value.clone(), &expr.bracket)?;
+        Ok(value)
+    }
+
+    /// Slices always produce a new list or string. Bounds may be negative
+    /// and are clamped to the length, so `xs[1:100]` never errors.
+    fn visit_slice_expr(&self, expr: &SliceExpr) -> Result<Object, SyntaxError> {
+        let object = self.evaluate(&expr.object)?;
+        let start = match &expr.start {
+            Some(start) => Some(self.evaluate(start)?),
+            None => None,
+        };
+        let end = match &expr.end {
+            Some(end) => Some(self.evaluate(end)?),
+            None => None,
+        };
+
+        match object {
+            Object::List(list) => {
+                let list = list.borrow();
+                let (start, end) =
+                    Interpreter::slice_bounds(start, end, list.len(), &expr.bracket)?;
+                Ok(Object::new_list(list[start..end].to_vec()))
+            }
+            Object::Str(s) => {
+                let graphemes: Vec<&str> = s.graphemes(true).collect();
+                let (start, end) =
+                    Interpreter::slice_bounds(start, end, graphemes.len(), &expr.bracket)?;
+                Ok(Object::Str(graphemes[start..end].concat()))
+            }
+            other => Err(SyntaxError::new(
+                expr.bracket.line,
+                expr.bracket.position,
+                SyntaxErrorTypes::NotIndexable(other.type_name()),
+            )),
+        }
+    }
+
+    fn visit_get_expr(&self, expr: &GetExpr) -> Result<Object, SyntaxError> {
+        match self.evaluate(&expr.object)? {
+            Object::Instance(instance) => instance.get(&expr.name),
+            Object::Nil if expr.safe => Err(SyntaxError::short_circuit(&expr.name)),
+            _ => Err(SyntaxError::new(
+                expr.name.line,
+                expr.name.position,
+                SyntaxErrorTypes::OnlyInstancesHaveProperties(),
+            )),
         }
     }
 
-    fn visit_variable_expr(&self, _expr: &VariableExpr) -> Result<Object, SyntaxError> {
-        Ok(Object::Nil)
+    /// A `?.` that found null unwinds to here, and the whole chain is null.
+    fn visit_safechain_expr(&self, expr: &SafeChainExpr) -> Result<Object, SyntaxError> {
+        match self.evaluate(&expr.expression) {
+            Err(error) => error.take_short_circuit().map(|_| Object::Nil),
+            result => result,
+        }
+    }
+
+    fn visit_set_expr(&self, expr: &SetExpr) -> Result<Object, SyntaxError> {
+        let instance = match self.evaluate(&expr.object)? {
+            Object::Instance(instance) => instance,
+            _ => {
+                return Err(SyntaxError::new(
+                    expr.name.line,
+                    expr.name.position,
+                    SyntaxErrorTypes::OnlyInstancesHaveProperties(),
+                ))
+            }
+        };
+
+        let value = self.evaluate(&expr.value)?;
+        instance.set(&expr.name, value.clone());
+        Ok(value)
+    }
+
+    /// `super` sits one environment outside the `this` binding of the
+    /// method being run, so both are found from the same resolved depth.
+    fn visit_super_expr(&self, expr: &SuperExpr) -> Result<Object, SyntaxError> {
+        let distance = expr
+            .depth
+            .get()
+            .expect("super is always resolved to a local scope");
+        let superclass = match self
+            .environment
+            .borrow()
+            .borrow()
+            .get_at(distance, &expr.keyword)?
+        {
+            Object::Class(class) => class,
+            _ => unreachable!("super is only ever bound to a class"),
+        };
+        let this = Token::new(
+            TokenType::THIS,
+            "this".to_string(),
+            Object::Nil,
+            expr.keyword.line,
+            expr.keyword.position,
+            expr.keyword.byte_offset,
+        );
+        let instance = match self
+            .environment
+            .borrow()
+            .borrow()
+            .get_at(distance - 1, &this)?
+        {
+            Object::Instance(instance) => instance,
+            _ => unreachable!("this is only ever bound to an instance"),
+        };
+
+        match superclass.find_method(&expr.method.lexeme) {
+            Some(method) => Ok(Object::Func(Rc::new(method.bind(&instance)))),
+            None => Err(SyntaxError::new(
+                expr.method.line,
+                expr.method.position,
+                SyntaxErrorTypes::UndefinedProperty(
+                    expr.method.lexeme.clone(),
+                    superclass.name().to_string(),
+                ),
+            )),
+        }
+    }
+
+    fn visit_this_expr(&self, expr: &ThisExpr) -> Result<Object, SyntaxError> {
+        self.look_up_variable(&expr.keyword, expr.depth.get())
+    }
+
+    fn visit_variable_expr(&self, expr: &VariableExpr) -> Result<Object, SyntaxError> {
+        self.look_up_variable(&expr.name, expr.depth.get())
     }
 }
 
 impl StmtVisitor<()> for Interpreter {
+    fn visit_block_stmt(&self, expr: &BlockStmt) -> Result<(), SyntaxError> {
+        let environment = Environment::new_enclosing(self.environment.borrow().clone());
+        self.execute_block(&expr.statements, environment)
+    }
+
+    fn visit_break_stmt(&self, expr: &BreakStmt) -> Result<(), SyntaxError> {
+        Err(SyntaxError::loop_control(&expr.keyword, &expr.label))
+    }
+
+    /// When the class has a superclass, its methods close over an extra
+    /// environment that binds `super`, matching the scope the resolver
+    /// introduced for it.
+    fn visit_class_stmt(&self, expr: &ClassStmt) -> Result<(), SyntaxError> {
+        let superclass = match &expr.superclass {
+            Some(Expr::Variable(superclass)) => {
+                match self.look_up_variable(&superclass.name, superclass.depth.get())? {
+                    Object::Class(class) => Some(class),
+                    _ => {
+                        return Err(SyntaxError::new(
+                            superclass.name.line,
+                            superclass.name.position,
+                            SyntaxErrorTypes::SuperclassNotClass(),
+                        ))
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let previous = superclass.as_ref().map(|superclass| {
+            let mut environment = Environment::new_enclosing(self.environment.borrow().clone());
+            environment.define("super", Object::Class(Rc::clone(superclass)));
+            self.environment.replace(Rc::new(RefCell::new(environment)))
+        });
+
+        let mut methods = HashMap::new();
+        for method in &expr.methods {
+            let function = CedarFunction::new(
+                method,
+                &self.environment.borrow(),
+                method.name.lexeme == "init",
+            );
+            methods.insert(method.name.lexeme.clone(), Rc::new(function));
+        }
+
+        if let Some(previous) = previous {
+            self.environment.replace(previous);
+        }
+
+        let class = CedarClass::new(&expr.name.lexeme, superclass, methods);
+        self.environment
+            .borrow()
+            .borrow_mut()
+            .define(&expr.name.lexeme, Object::Class(Rc::new(class)));
+        Ok(())
+    }
+
+    fn visit_continue_stmt(&self, expr: &ContinueStmt) -> Result<(), SyntaxError> {
+        Err(SyntaxError::loop_control(&expr.keyword, &expr.label))
+    }
+
     fn visit_expression_stmt(&self, expr: &ExpressionStmt) -> Result<(), SyntaxError> {
         self.evaluate(&expr.expression)?;
         Ok(())
     }
 
+    fn visit_function_stmt(&self, expr: &FunctionStmt) -> Result<(), SyntaxError> {
+        let function = CedarFunction::new(expr, &self.environment.borrow(), false);
+        self.environment
+            .borrow()
+            .borrow_mut()
+            .define(&expr.name.lexeme, Object::Func(Rc::new(function)));
+        Ok(())
+    }
+
+    fn visit_if_stmt(&self, expr: &IfStmt) -> Result<(), SyntaxError> {
+        if self.is_truthy(&self.evaluate(&expr.condition)?) {
+            self.execute(&expr.then_branch)
+        } else if let Some(else_branch) = &expr.else_branch {
+            self.execute(else_branch)
+        } else {
+            Ok(())
+        }
+    }
+
     fn visit_print_stmt(&self, expr: &PrintStmt) -> Result<(), SyntaxError> {
-        println!("{}", self.evaluate(&expr.expression)?.to_string());
+        println!("{}", self.evaluate(&expr.expression)?);
+        Ok(())
+    }
+
+    fn visit_return_stmt(&self, expr: &ReturnStmt) -> Result<(), SyntaxError> {
+        let value = match &expr.value {
+            Some(value) => self.evaluate(value)?,
+            None => Object::Nil,
+        };
+        Err(SyntaxError::return_value(&expr.keyword, value))
+    }
+
+    fn visit_var_stmt(&self, expr: &VarStmt) -> Result<(), SyntaxError> {
+        let value = match &expr.initializer {
+            Some(initializer) => self.evaluate(initializer)?,
+            None => Object::Nil,
+        };
+        let environment = self.environment.borrow();
+        let mut environment = environment.borrow_mut();
+        if expr.constant {
+            environment.define_constant(&expr.name, value);
+        } else {
+            environment.define(&expr.name.lexeme, value);
+        }
         Ok(())
     }
 
-    fn visit_var_stmt(&self, _expr: &VarStmt) -> Result<(), SyntaxError> {
+    fn visit_while_stmt(&self, expr: &WhileStmt) -> Result<(), SyntaxError> {
+        while self.is_truthy(&self.evaluate(&expr.condition)?) {
+            if let Err(error) = self.execute(&expr.body) {
+                match error.take_loop_control(&expr.label)? {
+                    LoopControl::Break => break,
+                    LoopControl::Continue => {}
+                }
+            }
+            if let Some(increment) = &expr.increment {
+                self.evaluate(increment)?;
+            }
+        }
         Ok(())
     }
 }
 
 impl Interpreter {
+    pub fn new() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        let interpreter = Interpreter {
+            globals: Rc::clone(&globals),
+            environment: RefCell::new(globals),
+            call_depth: Cell::new(0),
+        };
+
+        interpreter.define_native("clock", 0, natives::clock);
+        interpreter.define_native("input", 1, natives::input);
+        interpreter.define_native("len", 1, natives::len);
+        interpreter.define_native("chars", 1, natives::chars);
+        interpreter.define_native("bytes", 1, natives::bytes);
+        interpreter.define_native("keys", 1, natives::keys);
+        interpreter.define_native("values", 1, natives::values);
+        interpreter.define_native("type", 1, natives::type_of);
+        interpreter.define_native("str", 1, natives::str);
+        interpreter.define_native("num", 1, natives::num);
+        interpreter
+    }
+
+    /// Makes a Rust function callable from scripts under the given global
+    /// name. Calls are arity-checked like any other function.
+    pub fn define_native(&self, name: &str, arity: usize, function: NativeFn) {
+        let native = NativeFunction::new(name, arity, function);
+        self.globals
+            .borrow_mut()
+            .define(name, Object::Native(Rc::new(native)));
+    }
+
     fn evaluate(&self, expr: &Expr) -> Result<Object, SyntaxError> {
-        Ok(expr.accept(self)?)
+        expr.accept(self)
+    }
+
+    /// Variables the resolver bound to a local scope are read from exactly
+    /// that scope; anything it left unresolved is a global.
+    fn look_up_variable(&self, name: &Token, depth: Option<usize>) -> Result<Object, SyntaxError> {
+        match depth {
+            Some(distance) => self.environment.borrow().borrow().get_at(distance, name),
+            None => self.globals.borrow().get(name),
+        }
     }
 
     fn is_truthy(&self, object: &Object) -> bool {
@@ -105,15 +554,247 @@ impl Interpreter {
     }
     pub fn interpret(&mut self, statements: &[Stmt]) {
         for statement in statements {
-            self.execute(&statement);
+            if let Err(error) = self.execute(statement) {
+                error.report();
+                break;
+            }
+        }
+    }
+
+    pub fn execute(&self, stmt: &Stmt) -> Result<(), SyntaxError> {
+        stmt.accept(self)
+    }
+
+    /// Runs the statements with the given environment as the current
+    /// scope. The previous scope is put back before returning, whether
+    /// the statements finished normally or bailed out with an error.
+    pub fn execute_block(
+        &self,
+        statements: &[Stmt],
+        environment: Environment,
+    ) -> Result<(), SyntaxError> {
+        let previous = self.environment.replace(Rc::new(RefCell::new(environment)));
+
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+
+        self.environment.replace(previous);
+        result
+    }
+
+    /// Applies a binary arithmetic or comparison operator. Errors point
+    /// at `token`, which is the `+=` of a compound assignment or the `++`
+    /// of an update rather than a plain `+`.
+    fn apply_operator(
+        &self,
+        left: Object,
+        operator: TokenType,
+        right: Object,
+        token: &Token,
+    ) -> Result<Object, SyntaxError> {
+        let result = match operator {
+            TokenType::MINUS => left - right,
+            TokenType::SLASH => left / right,
+            TokenType::STAR => left * right,
+            TokenType::PLUS => left + right,
+            TokenType::MODULO => left % right,
+            TokenType::POW => left.pow(right),
+            TokenType::GREATER => Object::Bool(left > right),
+            TokenType::LESS => Object::Bool(left < right),
+            TokenType::GREATEREQUAL => Object::Bool(left >= right),
+            TokenType::LESSEQUAL => Object::Bool(left <= right),
+            TokenType::EQUALEQUAL => Object::Bool(left == right),
+            TokenType::BANGEQUAL => Object::Bool(left != right),
+
+            _ => Object::ArithmeticError,
+        };
+        if result == Object::ArithmeticError {
+            Err(SyntaxError::new(
+                token.line,
+                token.position,
+                SyntaxErrorTypes::InvalidArithmetic(token.lexeme.clone()),
+            ))
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Reads the variable, property or element `target` names, stores
+    /// `update(old)` in its place and returns the old and new values.
+    /// The object and index of the target are evaluated only once.
+    fn modify(
+        &self,
+        target: &Expr,
+        update: impl FnOnce(Object) -> Result<Object, SyntaxError>,
+    ) -> Result<(Object, Object), SyntaxError> {
+        match target {
+            Expr::Variable(variable) => {
+                let old = self.look_up_variable(&variable.name, variable.depth.get())?;
+                let new = update(old.clone())?;
+                self.assign_variable(&variable.name, variable.depth.get(), new.clone())?;
+                Ok((old, new))
+            }
+            Expr::Get(get) => {
+                let instance = match self.evaluate(&get.object)? {
+                    Object::Instance(instance) => instance,
+                    _ => {
+                        return Err(SyntaxError::new(
+                            get.name.line,
+                            get.name.position,
+                            SyntaxErrorTypes::OnlyInstancesHaveProperties(),
+                        ))
+                    }
+                };
+                let old = instance.get(&get.name)?;
+                let new = update(old.clone())?;
+                instance.set(&get.name, new.clone());
+                Ok((old, new))
+            }
+            Expr::Index(index) => {
+                let object = self.evaluate(&index.object)?;
+                let key = self.evaluate(&index.index)?;
+                let old = Interpreter::get_index(object.clone(), &key, &index.bracket)?;
+                let new = update(old.clone())?;
+                Interpreter::set_index(object, &key, new.clone(), &index.bracket)?;
+                Ok((old, new))
+            }
+            _ => unreachable!("the parser only allows variables, properties and elements"),
+        }
+    }
+
+    fn assign_variable(
+        &self,
+        name: &Token,
+        depth: Option<usize>,
+        value: Object,
+    ) -> Result<(), SyntaxError> {
+        match depth {
+            Some(distance) => self
+                .environment
+                .borrow()
+                .borrow_mut()
+                .assign_at(distance, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
         }
     }
 
-    pub fn execute(&mut self, stmt: &Stmt) -> Result<(), SyntaxError> {
-        Ok(stmt.accept(self)?)
+    fn get_index(object: Object, index: &Object, bracket: &Token) -> Result<Object, SyntaxError> {
+        match object {
+            Object::List(list) => {
+                let list = list.borrow();
+                let index = Interpreter::list_index(index, list.len(), bracket)?;
+                Ok(list[index].clone())
+            }
+            Object::Map(map) => {
+                let key = Interpreter::map_key(index, bracket)?;
+                match map.borrow().get(&key) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(SyntaxError::new(
+                        bracket.line,
+                        bracket.position,
+                        SyntaxErrorTypes::KeyNotFound(index.repr()),
+                    )),
+                }
+            }
+            // Strings index by grapheme cluster, so an accented letter or
+            // an emoji sequence is one element. `chars` and `bytes` give
+            // the other views.
+            Object::Str(s) => {
+                let graphemes: Vec<&str> = s.graphemes(true).collect();
+                let index = Interpreter::list_index(index, graphemes.len(), bracket)?;
+                Ok(Object::Str(graphemes[index].to_string()))
+            }
+            other => Err(SyntaxError::new(
+                bracket.line,
+                bracket.position,
+                SyntaxErrorTypes::NotIndexable(other.type_name()),
+            )),
+        }
     }
+
+    fn set_index(
+        object: Object,
+        index: &Object,
+        value: Object,
+        bracket: &Token,
+    ) -> Result<(), SyntaxError> {
+        match object {
+            Object::List(list) => {
+                let mut list = list.borrow_mut();
+                let index = Interpreter::list_index(index, list.len(), bracket)?;
+                list[index] = value;
+                Ok(())
+            }
+            Object::Map(map) => {
+                let key = Interpreter::map_key(index, bracket)?;
+                map.borrow_mut().insert(key, value);
+                Ok(())
+            }
+            other => Err(SyntaxError::new(
+                bracket.line,
+                bracket.position,
+                SyntaxErrorTypes::NotIndexable(other.type_name()),
+            )),
+        }
+    }
+
+    /// Turns an index value into a position in a sequence of length
+    /// `len`. Negative indices count back from the end.
+    fn list_index(index: &Object, len: usize, bracket: &Token) -> Result<usize, SyntaxError> {
+        let index = match index {
+            Object::Int(index) => *index,
+            other => {
+                return Err(SyntaxError::new(
+                    bracket.line,
+                    bracket.position,
+                    SyntaxErrorTypes::IndexNotInt(other.type_name()),
+                ))
+            }
+        };
+
+        let position = if index < 0 { index + len as i64 } else { index };
+        if position < 0 || position >= len as i64 {
+            return Err(SyntaxError::new(
+                bracket.line,
+                bracket.position,
+                SyntaxErrorTypes::IndexOutOfRange(index, len),
+            ));
+        }
+        Ok(position as usize)
+    }
+
+    fn map_key(key: &Object, bracket: &Token) -> Result<MapKey, SyntaxError> {
+        MapKey::from_object(key)
+            .map_err(|error_type| SyntaxError::new(bracket.line, bracket.position, error_type))
+    }
+
+    fn slice_bounds(
+        start: Option<Object>,
+        end: Option<Object>,
+        len: usize,
+        bracket: &Token,
+    ) -> Result<(usize, usize), SyntaxError> {
+        let clamp = |bound: Option<Object>, default: usize| match bound {
+            None => Ok(default),
+            Some(Object::Int(bound)) => {
+                let bound = if bound < 0 { bound + len as i64 } else { bound };
+                Ok(bound.clamp(0, len as i64) as usize)
+            }
+            Some(other) => Err(SyntaxError::new(
+                bracket.line,
+                bracket.position,
+                SyntaxErrorTypes::IndexNotInt(other.type_name()),
+            )),
+        };
+
+        let start = clamp(start, 0)?;
+        let end = clamp(end, len)?;
+        Ok((start, end.max(start)))
+    }
+
     pub fn check_number_operand(_operator: Token, operand: &Object) -> Result<(), SyntaxError> {
-        if let Object::Num(_) = operand {
+        if let Object::Int(_) | Object::Float(_) = operand {
             Ok(())
         } else {
             Err(SyntaxError::new(0, 0, SyntaxErrorTypes::InvalidUnary()))
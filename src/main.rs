@@ -1,7 +1,15 @@
 mod ast_printer;
+mod callable;
+mod cedar_class;
+mod cedar_function;
+mod cedar_instance;
+mod cedar_map;
+mod environment;
 mod error;
 mod expr;
 mod interpreter;
+mod native_function;
+mod natives;
 mod stmt;
 mod tokens {
     pub(crate) mod token;
@@ -13,24 +21,35 @@ mod errors {
 }
 mod object;
 mod parser;
+mod resolver;
 mod scanner;
 // mod expr;
 
 use errors::lexer_error::LexerError;
 use interpreter::*;
 use parser::Parser;
+use resolver::Resolver;
 use scanner::*;
 use std::env::args;
-use std::io::{self, stdout, BufRead, Write};
+use std::io::{self, stdout, Write};
 
 struct Cedar {
     interpreter: Interpreter,
+    resolver: Resolver,
+    tab_width: usize,
 }
 
 impl Cedar {
     pub fn new() -> Cedar {
+        // Columns in diagnostics count a tab as this many spaces.
+        let tab_width = std::env::var("CEDAR_TAB_WIDTH")
+            .ok()
+            .and_then(|width| width.parse().ok())
+            .unwrap_or(4);
         Cedar {
-            interpreter: Interpreter {},
+            interpreter: Interpreter::new(),
+            resolver: Resolver::new(),
+            tab_width,
         }
     }
 
@@ -44,26 +63,31 @@ impl Cedar {
         Ok(())
     }
 
+    /// Reads and runs one line at a time. The stdin lock is only held
+    /// while a line is read, so `input()` can read from it while the line
+    /// runs.
     fn run_prompt(&mut self) {
-        let stdin = io::stdin();
-        print!("> ");
-        let _ = stdout().flush();
-        for line in stdin.lock().lines() {
-            if let Ok(line) = line {
-                if line.is_empty() {
-                    break;
-                }
-                let _ = self.run(line, "Prompt Error".to_string());
-            } else {
-                break;
-            }
+        let mut line = String::new();
+        loop {
             print!("> ");
             let _ = stdout().flush();
+
+            line.clear();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                break;
+            }
+            let _ = self.run(line.to_string(), "Prompt Error".to_string());
         }
     }
 
     fn run(&mut self, source: String, file_name: String) -> Result<(), LexerError> {
-        let mut scanner = Scanner::new(source.chars().collect(), file_name);
+        let mut scanner =
+            Scanner::new(source.chars().collect(), file_name).with_tab_width(self.tab_width);
         let tokens = scanner.scan_tokens();
 
         // for token in tokens? {
@@ -73,27 +97,39 @@ impl Cedar {
         let mut parser = Parser::new(tokens.unwrap().clone());
         //let mut statements: Vec<Stmt> = parser.parse().unwrap();
         match parser.parse() {
-            Ok(statements) => {
-                self.interpreter.interpret(&statements);
-            }
+            Ok(statements) => match self.resolver.resolve_program(&statements) {
+                Ok(()) => self.interpreter.interpret(&statements),
+                Err(error) => error.report(),
+            },
             Err(error) => error.report(),
         }
         Ok(())
     }
 }
 
+/// The interpreter walks the tree recursively, several Rust frames per
+/// Cedar call, so it runs on a thread with room for the interpreter's
+/// call-depth limit rather than on the main thread's default stack.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 fn main() {
     let args: Vec<String> = args().collect();
-    let mut cedar = Cedar::new();
     //println!("{}", args[1]);
-    match args.len() {
-        1 => cedar.run_prompt(),
-        2 => cedar.run_file(&args[1]).expect("Could not run file"),
-        _ => {
-            println!("Usage: lox-ast [script]");
-            std::process::exit(64);
-        }
-    }
+    let interpreter = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let mut cedar = Cedar::new();
+            match args.len() {
+                1 => cedar.run_prompt(),
+                2 => cedar.run_file(&args[1]).expect("Could not run file"),
+                _ => {
+                    println!("Usage: lox-ast [script]");
+                    std::process::exit(64);
+                }
+            }
+        })
+        .expect("Could not start the interpreter thread");
+    let _ = interpreter.join();
 }
 
 // struct KayLan {
@@ -0,0 +1,63 @@
+use core::fmt;
+use std::rc::Rc;
+
+use crate::callable::*;
+use crate::errors::syntax_error::{SyntaxError, SyntaxErrorTypes};
+use crate::interpreter::*;
+use crate::object::*;
+use crate::tokens::token::*;
+
+/// The Rust side of a built-in. Natives report failures as bare error
+/// types; the location is filled in from the call site.
+pub type NativeFn = fn(&[Object]) -> Result<Object, SyntaxErrorTypes>;
+
+pub struct NativeFunction {
+    name: String,
+    arity: usize,
+    function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(name: &str, arity: usize, function: NativeFn) -> NativeFunction {
+        NativeFunction {
+            name: name.to_string(),
+            arity,
+            function,
+        }
+    }
+}
+
+impl CedarCallable for NativeFunction {
+    fn call(
+        self: Rc<Self>,
+        _interpreter: &Interpreter,
+        arguments: Vec<Object>,
+        paren: &Token,
+    ) -> Result<Object, SyntaxError> {
+        (self.function)(&arguments)
+            .map_err(|error_type| SyntaxError::new(paren.line, paren.position, error_type))
+    }
+
+    fn arity(&self) -> usize {
+        self.arity
+    }
+}
+
+impl fmt::Display for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<native func {}>", self.name)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Natives compare by identity.
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{self, BufRead, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::cedar_map::*;
+use crate::errors::syntax_error::SyntaxErrorTypes;
+use crate::object::*;
+
+/// Seconds since the Unix epoch, with sub-second precision.
+pub fn clock(_arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(Object::Float(elapsed.as_secs_f64()))
+}
+
+/// Prints the prompt and reads one line from stdin, without its line
+/// ending. Returns null once stdin is exhausted.
+pub fn input(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    print!("{}", arguments[0]);
+    let _ = io::stdout().flush();
+
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => Ok(Object::Nil),
+        Ok(_) => Ok(Object::Str(line.trim_end_matches(['\n', '\r']).to_string())),
+    }
+}
+
+/// The length of a string counts grapheme clusters, what a reader would
+/// call characters: `len("e\u{301}")` is 1. Use `len(chars(s))` or
+/// `len(bytes(s))` for code points or UTF-8 bytes.
+pub fn len(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Str(s) => Ok(Object::Int(s.graphemes(true).count() as i64)),
+        Object::List(x) => Ok(Object::Int(x.borrow().len() as i64)),
+        Object::Map(x) => Ok(Object::Int(x.borrow().len() as i64)),
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "len".to_string(),
+            "a string, list or map".to_string(),
+            other.type_name(),
+        )),
+    }
+}
+
+/// The Unicode code points of a string, each as a one-character string.
+pub fn chars(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Str(s) => Ok(Object::new_list(
+            s.chars().map(|c| Object::Str(c.to_string())).collect(),
+        )),
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "chars".to_string(),
+            "a string".to_string(),
+            other.type_name(),
+        )),
+    }
+}
+
+/// The UTF-8 encoding of a string as a list of ints.
+pub fn bytes(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Str(s) => Ok(Object::new_list(
+            s.bytes().map(|b| Object::Int(b as i64)).collect(),
+        )),
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "bytes".to_string(),
+            "a string".to_string(),
+            other.type_name(),
+        )),
+    }
+}
+
+/// The keys of a map as a new list, in insertion order.
+pub fn keys(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Map(x) => Ok(Object::new_list(
+            x.borrow().keys().map(MapKey::to_object).collect(),
+        )),
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "keys".to_string(),
+            "a map".to_string(),
+            other.type_name(),
+        )),
+    }
+}
+
+/// The values of a map as a new list, in insertion order.
+pub fn values(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Map(x) => Ok(Object::new_list(x.borrow().values().cloned().collect())),
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "values".to_string(),
+            "a map".to_string(),
+            other.type_name(),
+        )),
+    }
+}
+
+pub fn type_of(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    Ok(Object::Str(arguments[0].type_name()))
+}
+
+pub fn str(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    Ok(Object::Str(arguments[0].to_string()))
+}
+
+/// Converts strings and booleans to numbers. A string that reads as a
+/// whole number becomes an int, anything else numeric a float.
+/// Surrounding whitespace in a string is ignored.
+pub fn num(arguments: &[Object]) -> Result<Object, SyntaxErrorTypes> {
+    match &arguments[0] {
+        Object::Int(x) => Ok(Object::Int(*x)),
+        Object::Float(x) => Ok(Object::Float(*x)),
+        Object::Bool(x) => Ok(Object::Int(*x as i64)),
+        Object::Str(s) => {
+            let s = s.trim();
+            if let Ok(x) = s.parse::<i64>() {
+                Ok(Object::Int(x))
+            } else if let Ok(x) = s.parse::<f64>() {
+                Ok(Object::Float(x))
+            } else {
+                Err(SyntaxErrorTypes::NumberConversion(s.to_string()))
+            }
+        }
+        other => Err(SyntaxErrorTypes::InvalidArgument(
+            "num".to_string(),
+            "a string, number or bool".to_string(),
+            other.type_name(),
+        )),
+    }
+}
@@ -1,27 +1,90 @@
 use core::fmt;
 use std::{
+    cell::RefCell,
     cmp::Ordering,
     ops::{Add, Div, Mul, Rem, Sub},
+    rc::Rc,
+    thread::LocalKey,
 };
 
 use num_traits::pow::Pow;
 
-// TODO: Seprate floating point with int
-#[derive(Debug, Clone, PartialEq)]
+use crate::cedar_class::*;
+use crate::cedar_function::*;
+use crate::cedar_instance::*;
+use crate::cedar_map::*;
+use crate::native_function::*;
+
+#[derive(Debug, Clone)]
 pub enum Object {
-    Num(f64),
+    Int(i64),
+    Float(f64),
     Str(String),
     Bool(bool),
+    List(Rc<RefCell<Vec<Object>>>),
+    Map(Rc<RefCell<CedarMap>>),
+    Func(Rc<CedarFunction>),
+    Class(Rc<CedarClass>),
+    Instance(Rc<CedarInstance>),
+    Native(Rc<NativeFunction>),
     Nil,
     // TODO: Update arithmatic error
     ArithmeticError,
 }
 
+thread_local! {
+    /// Lists and maps that are part way through being printed or compared.
+    /// Lists and maps are shared by reference, so one can end up holding
+    /// itself, and walking it again would never finish.
+    static PRINTING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    static COMPARING: RefCell<Vec<(usize, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `visit` unless `key` is already being visited further up the
+/// stack, in which case there is nothing new to see and None is returned.
+fn visit_once<K: PartialEq, T>(
+    stack: &'static LocalKey<RefCell<Vec<K>>>,
+    key: K,
+    visit: impl FnOnce() -> T,
+) -> Option<T> {
+    if stack.with(|stack| stack.borrow().contains(&key)) {
+        return None;
+    }
+    stack.with(|stack| stack.borrow_mut().push(key));
+    let result = visit();
+    stack.with(|stack| stack.borrow_mut().pop());
+    Some(result)
+}
+
+/// Identifies a shared list or map by the address of its contents.
+fn address<T>(value: &Rc<RefCell<T>>) -> usize {
+    Rc::as_ptr(value) as usize
+}
+
 impl fmt::Display for Object {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Object::Num(x) => write!(f, "{x}"),
+            Object::Int(x) => write!(f, "{x}"),
+            // Debug formatting keeps the ".0" on whole floats, so 3.0
+            // does not print the same as the int 3.
+            Object::Float(x) => write!(f, "{x:?}"),
             Object::Str(x) => write!(f, "{x}"),
+            // A list or map inside itself prints as `[...]` or `{...}`.
+            Object::List(x) => {
+                let elements = visit_once(&PRINTING, address(x), || {
+                    let elements: Vec<String> = x.borrow().iter().map(Object::repr).collect();
+                    elements.join(", ")
+                });
+                write!(f, "[{}]", elements.as_deref().unwrap_or("..."))
+            }
+            Object::Map(x) => match visit_once(&PRINTING, address(x), || x.borrow().to_string()) {
+                Some(map) => write!(f, "{map}"),
+                None => write!(f, "{{...}}"),
+            },
+            Object::Func(x) => write!(f, "{x}"),
+            Object::Class(x) => write!(f, "{x}"),
+            Object::Instance(x) => write!(f, "{x}"),
+            Object::Native(x) => write!(f, "{x}"),
             Object::Nil => write!(f, "Nil"),
             Object::Bool(x) => {
                 if *x {
@@ -35,13 +98,73 @@ impl fmt::Display for Object {
     }
 }
 
+impl Object {
+    /// The name of the value's type as the language presents it, used by
+    /// the `type` built-in and in argument errors. Instances report the
+    /// name of their class.
+    pub fn type_name(&self) -> String {
+        match self {
+            Object::Int(_) => "int".to_string(),
+            Object::Float(_) => "float".to_string(),
+            Object::Str(_) => "string".to_string(),
+            Object::Bool(_) => "bool".to_string(),
+            Object::List(_) => "list".to_string(),
+            Object::Map(_) => "map".to_string(),
+            Object::Func(_) | Object::Native(_) => "function".to_string(),
+            Object::Class(_) => "class".to_string(),
+            Object::Instance(x) => x.class_name().to_string(),
+            Object::Nil => "null".to_string(),
+            Object::ArithmeticError => "error".to_string(),
+        }
+    }
+
+    /// How a value is shown when nested inside a collection: strings are
+    /// quoted so `["1"]` and `[1]` print differently.
+    pub fn repr(&self) -> String {
+        match self {
+            Object::Str(x) => format!("{:?}", x),
+            other => other.to_string(),
+        }
+    }
+
+    pub fn new_list(elements: Vec<Object>) -> Object {
+        Object::List(Rc::new(RefCell::new(elements)))
+    }
+
+    pub fn new_map(map: CedarMap) -> Object {
+        Object::Map(Rc::new(RefCell::new(map)))
+    }
+}
+
+/// Numbers of mixed type are compared and combined as floats. Returns
+/// None unless both operands are numbers.
+fn as_floats(left: &Object, right: &Object) -> Option<(f64, f64)> {
+    let left = match left {
+        Object::Int(x) => *x as f64,
+        Object::Float(x) => *x,
+        _ => return None,
+    };
+    let right = match right {
+        Object::Int(x) => *x as f64,
+        Object::Float(x) => *x,
+        _ => return None,
+    };
+    Some((left, right))
+}
+
 impl Sub for Object {
     type Output = Object;
 
     fn sub(self, other: Self) -> Object {
-        match (self, other) {
-            (Object::Num(left), Object::Num(right)) => Object::Num(left - right),
-            _ => Object::ArithmeticError,
+        if let (Object::Int(left), Object::Int(right)) = (&self, &other) {
+            return left
+                .checked_sub(*right)
+                .map_or(Object::ArithmeticError, Object::Int);
+        }
+
+        match as_floats(&self, &other) {
+            Some((left, right)) => Object::Float(left - right),
+            None => Object::ArithmeticError,
         }
     }
 }
@@ -49,19 +172,19 @@ impl Sub for Object {
 impl Div for Object {
     type Output = Object;
 
+    /// Dividing two ints is integer division, truncating towards zero.
+    /// As soon as either side is a float the result is a float.
     fn div(self, other: Self) -> Object {
-        match other {
-            Object::Num(other) => {
-                if other == 0.0 {
-                    return Object::ArithmeticError;
-                }
-            }
-            _ => (),
+        if let (Object::Int(left), Object::Int(right)) = (&self, &other) {
+            return left
+                .checked_div(*right)
+                .map_or(Object::ArithmeticError, Object::Int);
         }
 
-        match (self, other) {
-            (Object::Num(left), Object::Num(right)) => Object::Num(left / right),
-            _ => Object::ArithmeticError,
+        match as_floats(&self, &other) {
+            Some((_, 0.0)) => Object::ArithmeticError,
+            Some((left, right)) => Object::Float(left / right),
+            None => Object::ArithmeticError,
         }
     }
 }
@@ -70,9 +193,15 @@ impl Mul for Object {
     type Output = Object;
 
     fn mul(self, other: Self) -> Object {
-        match (self, other) {
-            (Object::Num(left), Object::Num(right)) => Object::Num(left * right),
-            _ => Object::ArithmeticError,
+        if let (Object::Int(left), Object::Int(right)) = (&self, &other) {
+            return left
+                .checked_mul(*right)
+                .map_or(Object::ArithmeticError, Object::Int);
+        }
+
+        match as_floats(&self, &other) {
+            Some((left, right)) => Object::Float(left * right),
+            None => Object::ArithmeticError,
         }
     }
 }
@@ -82,15 +211,20 @@ impl Add for Object {
 
     fn add(self, other: Self) -> Object {
         match (self, other) {
-            (Object::Str(left), Object::Num(right)) => {
-                Object::Str(format!("{}{}", left, right.to_string()))
+            (Object::Str(left), Object::Str(right)) => Object::Str(format!("{}{}", left, right)),
+            (Object::Str(left), right @ (Object::Int(_) | Object::Float(_))) => {
+                Object::Str(format!("{}{}", left, right))
             }
-            (Object::Num(left), Object::Str(right)) => {
-                Object::Str(format!("{}{}", left.to_string(), right))
+            (left @ (Object::Int(_) | Object::Float(_)), Object::Str(right)) => {
+                Object::Str(format!("{}{}", left, right))
             }
-            (Object::Str(left), Object::Str(right)) => Object::Str(format!("{}{}", left, right)),
-            (Object::Num(left), Object::Num(right)) => Object::Num(left + right),
-            _ => Object::ArithmeticError,
+            (Object::Int(left), Object::Int(right)) => left
+                .checked_add(right)
+                .map_or(Object::ArithmeticError, Object::Int),
+            (left, right) => match as_floats(&left, &right) {
+                Some((left, right)) => Object::Float(left + right),
+                None => Object::ArithmeticError,
+            },
         }
     }
 }
@@ -98,10 +232,21 @@ impl Add for Object {
 impl Pow<Object> for Object {
     type Output = Object;
 
+    /// An int raised to a non-negative int stays an int; a negative
+    /// exponent or any float operand gives a float.
     fn pow(self, other: Self) -> Object {
-        match (self, other) {
-            (Object::Num(left), Object::Num(right)) => Object::Num(left.powf(right)),
-            _ => Object::ArithmeticError,
+        if let (Object::Int(left), Object::Int(right)) = (&self, &other) {
+            if *right >= 0 {
+                return u32::try_from(*right)
+                    .ok()
+                    .and_then(|right| left.checked_pow(right))
+                    .map_or(Object::ArithmeticError, Object::Int);
+            }
+        }
+
+        match as_floats(&self, &other) {
+            Some((left, right)) => Object::Float(left.powf(right)),
+            None => Object::ArithmeticError,
         }
     }
 }
@@ -109,30 +254,65 @@ impl Pow<Object> for Object {
 impl Rem for Object {
     type Output = Object;
 
+    /// The remainder takes the sign of the dividend, matching the
+    /// truncating integer division above.
     fn rem(self, other: Self) -> Object {
-        match (self, other) {
-            (Object::Num(left), Object::Num(right)) => Object::Num(left % right),
-            _ => Object::ArithmeticError,
+        if let (Object::Int(left), Object::Int(right)) = (&self, &other) {
+            return left
+                .checked_rem(*right)
+                .map_or(Object::ArithmeticError, Object::Int);
+        }
+
+        match as_floats(&self, &other) {
+            Some((left, right)) => Object::Float(left % right),
+            None => Object::ArithmeticError,
         }
     }
 }
 
-impl PartialOrd for Object {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+/// Ints and floats compare by numeric value, so `1 == 1.0`. Everything
+/// else compares within its own variant only. When comparing two lists
+/// or maps leads back to the same pair, that pair is taken as equal so
+/// far, which lets self-containing lists be compared at all.
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (Object::Num(left), Object::Num(right)) => left.partial_cmp(right),
-            _ => None,
+            (Object::Int(left), Object::Int(right)) => left == right,
+            (Object::Int(_) | Object::Float(_), Object::Int(_) | Object::Float(_)) => {
+                as_floats(self, other).is_some_and(|(left, right)| left == right)
+            }
+            (Object::Str(left), Object::Str(right)) => left == right,
+            (Object::Bool(left), Object::Bool(right)) => left == right,
+            (Object::List(left), Object::List(right)) => {
+                Rc::ptr_eq(left, right)
+                    || visit_once(&COMPARING, (address(left), address(right)), || {
+                        *left.borrow() == *right.borrow()
+                    })
+                    .unwrap_or(true)
+            }
+            (Object::Map(left), Object::Map(right)) => {
+                Rc::ptr_eq(left, right)
+                    || visit_once(&COMPARING, (address(left), address(right)), || {
+                        *left.borrow() == *right.borrow()
+                    })
+                    .unwrap_or(true)
+            }
+            (Object::Func(left), Object::Func(right)) => left == right,
+            (Object::Class(left), Object::Class(right)) => left == right,
+            (Object::Instance(left), Object::Instance(right)) => left == right,
+            (Object::Native(left), Object::Native(right)) => left == right,
+            (Object::Nil, Object::Nil) => true,
+            (Object::ArithmeticError, Object::ArithmeticError) => true,
+            _ => false,
         }
     }
 }
 
-impl Ord for Object {
-    fn cmp(&self, other: &Self) -> Ordering {
+impl PartialOrd for Object {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
-            (Object::Num(left), Object::Num(right)) => left.partial_cmp(right).unwrap(),
-            _ => Ordering::Equal,
+            (Object::Int(left), Object::Int(right)) => left.partial_cmp(right),
+            _ => as_floats(self, other).and_then(|(left, right)| left.partial_cmp(&right)),
         }
     }
 }
-
-impl Eq for Object {}
@@ -1,11 +1,22 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
 use crate::errors::syntax_error::SyntaxError;
 use crate::errors::syntax_error::SyntaxErrorTypes;
 use crate::expr::*;
 use crate::object::*;
+use crate::stmt::BlockStmt;
+use crate::stmt::BreakStmt;
+use crate::stmt::ClassStmt;
+use crate::stmt::ContinueStmt;
 use crate::stmt::ExpressionStmt;
+use crate::stmt::FunctionStmt;
+use crate::stmt::IfStmt;
 use crate::stmt::PrintStmt;
+use crate::stmt::ReturnStmt;
 use crate::stmt::Stmt;
-// use crate::stmt::VarStmt;
+use crate::stmt::VarStmt;
+use crate::stmt::WhileStmt;
 use crate::tokens::token::*;
 use crate::tokens::token_type::*;
 pub struct Parser {
@@ -14,16 +25,22 @@ pub struct Parser {
 }
 
 impl Parser {
+    /// Doc comments are only of interest to tooling reading the token
+    /// stream, so they are dropped before parsing.
     pub fn new(tokens: Vec<Token>) -> Parser {
+        let tokens = tokens
+            .into_iter()
+            .filter(|token| token.ttype != TokenType::DOCCOMMENT)
+            .collect();
         Parser { tokens, current: 0 }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>, SyntaxError> {
         let mut statements: Vec<Stmt> = Vec::new();
         while !self.is_at_end() {
-            statements.push(self.statement()?);
+            statements.push(self.declaration()?);
         }
-        return Ok(statements);
+        Ok(statements)
     }
     // pub fn synchronize(&mut self) {
     //     self.advance();
@@ -75,14 +92,172 @@ impl Parser {
     //     }
     // }
 
+    /// Parses a full expression. Each precedence level has its own
+    /// method that calls down into the next tighter one; from loosest to
+    /// tightest binding:
+    ///
+    /// | Level      | Operators           | Associativity |
+    /// |------------|---------------------|---------------|
+    /// | assignment | `=` `+=` `-=` etc.  | right         |
+    /// | ternary    | `?` `:`             | right         |
+    /// | coalesce   | `??`                | left          |
+    /// | or         | `\|\|`              | left          |
+    /// | and        | `&&`                | left          |
+    /// | equality   | `==` `!=`           | left          |
+    /// | comparison | `>` `>=` `<` `<=`   | left          |
+    /// | term       | `+` `-`             | left          |
+    /// | factor     | `*` `/` `%`         | left          |
+    /// | unary      | `!` `-` `++` `--`   | right         |
+    /// | power      | `^`                 | right         |
+    /// | call       | `()` `.` `?.` `[]`  | left          |
+    ///
+    /// Power binds tighter than unary minus, so `-2 ^ 2` is `-(2 ^ 2)`,
+    /// while its right operand may itself be negated, as in `2 ^ -1`.
+    /// Postfix `++` and `--` come last at the call level, so `-x++` is
+    /// `-(x++)`. `??` binds looser than `||`, so `a ?? b || c` is
+    /// `a ?? (b || c)`.
     pub fn expression(&mut self) -> Result<Expr, SyntaxError> {
-        self.equality()
+        self.assignment()
+    }
+
+    /// Assignment is right-associative, so the value side recurses back
+    /// into assignment. The left side is parsed as an ordinary expression
+    /// first and only accepted when it turns out to be a variable, a
+    /// property access or an index.
+    fn assignment(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = self.ternary()?;
+
+        if self.is_match(&[TokenType::EQUAL]) {
+            let equals = self.previous();
+            let value = self.assignment()?;
+
+            if let Expr::Variable(expr) = expr {
+                return Ok(Expr::Assign(AssignExpr {
+                    name: expr.name,
+                    value: Box::new(value),
+                    depth: Cell::new(None),
+                }));
+            }
+
+            if let Expr::Index(index) = expr {
+                return Ok(Expr::SetIndex(SetIndexExpr {
+                    object: index.object,
+                    bracket: index.bracket,
+                    index: index.index,
+                    value: Box::new(value),
+                }));
+            }
+
+            if let Expr::Get(get) = expr {
+                return Ok(Expr::Set(SetExpr {
+                    object: get.object,
+                    name: get.name,
+                    value: Box::new(value),
+                }));
+            }
+
+            return Err(SyntaxError::new(
+                equals.line,
+                equals.position,
+                SyntaxErrorTypes::InvalidAssignmentTarget(),
+            ));
+        }
+
+        if self.is_match(&[
+            TokenType::PLUSEQUAL,
+            TokenType::MINUSEQUAL,
+            TokenType::STAREQUAL,
+            TokenType::SLASHEQUAL,
+            TokenType::MODULOEQUAL,
+            TokenType::POWEQUAL,
+        ]) {
+            let operator = self.previous();
+            let value = self.assignment()?;
+            check_place(&expr, &operator)?;
+            return Ok(Expr::Compound(CompoundExpr {
+                target: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            }));
+        }
+
+        Ok(expr)
+    }
+
+    /// `cond ? a : b`. The middle operand is a full expression, as it is
+    /// closed off by the colon; the last one recurses so that chained
+    /// conditionals group to the right.
+    fn ternary(&mut self) -> Result<Expr, SyntaxError> {
+        let condition = self.coalesce()?;
+
+        if self.is_match(&[TokenType::QUESTION]) {
+            let then_branch = self.expression()?;
+            self.consume(TokenType::COLON, ":")?;
+            let else_branch = self.ternary()?;
+            return Ok(Expr::Conditional(ConditionalExpr {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            }));
+        }
+
+        Ok(condition)
+    }
+
+    /// `a ?? b` is a logical operator: `b` is only evaluated when `a` is
+    /// null.
+    fn coalesce(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.or()?;
+
+        while self.is_match(&[TokenType::QUESTIONQUESTION]) {
+            let operator = self.previous();
+            let right = self.or()?;
+            expr = Expr::Logical(LogicalExpr {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.and()?;
+
+        while self.is_match(&[TokenType::OR]) {
+            let operator = self.previous();
+            let right = self.and()?;
+            expr = Expr::Logical(LogicalExpr {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.equality()?;
+
+        while self.is_match(&[TokenType::AND]) {
+            let operator = self.previous();
+            let right = self.equality()?;
+            expr = Expr::Logical(LogicalExpr {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
     }
 
     fn equality(&mut self) -> Result<Expr, SyntaxError> {
         let mut expr = self.comparison()?;
 
-        while self.is_match(&[TokenType::BANGEQUAL, TokenType::EQUAL]) {
+        while self.is_match(&[TokenType::BANGEQUAL, TokenType::EQUALEQUAL]) {
             let operator = self.previous();
             let right = self.comparison()?;
             expr = Ok(Expr::Binary(BinaryExpr {
@@ -135,12 +310,7 @@ impl Parser {
     fn factor(&mut self) -> Result<Expr, SyntaxError> {
         let mut expr = self.unary()?;
 
-        while self.is_match(&[
-            TokenType::SLASH,
-            TokenType::STAR,
-            TokenType::POW,
-            TokenType::MODULO,
-        ]) {
+        while self.is_match(&[TokenType::SLASH, TokenType::STAR, TokenType::MODULO]) {
             let operator = self.previous();
             let right = self.unary()?;
             expr = Ok(Expr::Binary(BinaryExpr {
@@ -162,8 +332,146 @@ impl Parser {
                 right: Box::new(right),
             }));
         }
+        if self.is_match(&[TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+            let operator = self.previous();
+            let target = self.unary()?;
+            check_place(&target, &operator)?;
+            return Ok(Expr::Update(UpdateExpr {
+                target: Box::new(target),
+                operator,
+                prefix: true,
+            }));
+        }
 
-        Ok(self.primary()?)
+        self.power()
+    }
+
+    /// Exponentiation is right-associative: the right operand recurses
+    /// through unary, which lands back here, so `2 ^ 3 ^ 2` is
+    /// `2 ^ (3 ^ 2)`.
+    fn power(&mut self) -> Result<Expr, SyntaxError> {
+        let expr = self.call()?;
+
+        if self.is_match(&[TokenType::POW]) {
+            let operator = self.previous();
+            let right = self.unary()?;
+            return Ok(Expr::Binary(BinaryExpr {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            }));
+        }
+
+        Ok(expr)
+    }
+
+    /// A chain containing `?.` is wrapped in a SafeChain once it ends, so
+    /// a null before a `?.` skips every later `.`, `()` and `[]` as well:
+    /// `a?.b.c()` is null when `a` is. The wrapper is not an assignment
+    /// target, which also rules out `a?.b = 1`.
+    fn call(&mut self) -> Result<Expr, SyntaxError> {
+        let mut expr = self.primary()?;
+        let mut safe_chain = false;
+
+        loop {
+            if self.is_match(&[TokenType::LEFTPAREN]) {
+                expr = self.finish_call(expr, false)?;
+            } else if self.is_match(&[TokenType::LEFTBRACKET]) {
+                expr = self.finish_index(expr)?;
+            } else if self.is_match(&[TokenType::DOT]) {
+                let name = self.consume(TokenType::IDENTIFIER, "property name")?;
+                expr = Expr::Get(GetExpr {
+                    object: Box::new(expr),
+                    name,
+                    safe: false,
+                });
+            } else if self.is_match(&[TokenType::QUESTIONDOT]) {
+                safe_chain = true;
+                if self.is_match(&[TokenType::LEFTPAREN]) {
+                    expr = self.finish_call(expr, true)?;
+                } else {
+                    let name = self.consume(TokenType::IDENTIFIER, "property name")?;
+                    expr = Expr::Get(GetExpr {
+                        object: Box::new(expr),
+                        name,
+                        safe: true,
+                    });
+                }
+            } else {
+                break;
+            }
+        }
+
+        if safe_chain {
+            expr = Expr::SafeChain(SafeChainExpr {
+                expression: Box::new(expr),
+            });
+        }
+
+        if self.is_match(&[TokenType::PLUSPLUS, TokenType::MINUSMINUS]) {
+            let operator = self.previous();
+            check_place(&expr, &operator)?;
+            expr = Expr::Update(UpdateExpr {
+                target: Box::new(expr),
+                operator,
+                prefix: false,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses `[i]` or a slice `[a:b]` where either bound may be left
+    /// out. The opening bracket has already been consumed.
+    fn finish_index(&mut self, object: Expr) -> Result<Expr, SyntaxError> {
+        let bracket = self.previous();
+
+        let start = if self.check(TokenType::COLON) {
+            None
+        } else {
+            Some(Box::new(self.expression()?))
+        };
+
+        if self.is_match(&[TokenType::COLON]) {
+            let end = if self.check(TokenType::RIGHTBRACKET) {
+                None
+            } else {
+                Some(Box::new(self.expression()?))
+            };
+            self.consume(TokenType::RIGHTBRACKET, "]")?;
+            return Ok(Expr::Slice(SliceExpr {
+                object: Box::new(object),
+                bracket,
+                start,
+                end,
+            }));
+        }
+
+        self.consume(TokenType::RIGHTBRACKET, "]")?;
+        Ok(Expr::Index(IndexExpr {
+            object: Box::new(object),
+            bracket,
+            index: start.expect("an index without a colon always has a start"),
+        }))
+    }
+
+    fn finish_call(&mut self, callee: Expr, safe: bool) -> Result<Expr, SyntaxError> {
+        let mut arguments = Vec::new();
+
+        if !self.check(TokenType::RIGHTPAREN) {
+            arguments.push(self.expression()?);
+            while self.is_match(&[TokenType::COMMA]) {
+                arguments.push(self.expression()?);
+            }
+        }
+
+        let paren = self.consume(TokenType::RIGHTPAREN, ")")?;
+        Ok(Expr::Call(CallExpr {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+            safe,
+        }))
     }
 
     fn primary(&mut self) -> Result<Expr, SyntaxError> {
@@ -185,20 +493,51 @@ impl Parser {
             }));
         }
 
+        if self.is_match(&[TokenType::INTERPOLATION]) {
+            return self.interpolation();
+        }
         if self.is_match(&[TokenType::NUMBER, TokenType::STRING]) {
             return Ok(Expr::Literal(LiteralExpr {
                 value: Some(self.previous().literal),
             }));
         }
 
+        if self.is_match(&[TokenType::SUPER]) {
+            let keyword = self.previous();
+            self.consume(TokenType::DOT, ".")?;
+            let method = self.consume(TokenType::IDENTIFIER, "superclass method name")?;
+            return Ok(Expr::Super(SuperExpr {
+                keyword,
+                method,
+                depth: Cell::new(None),
+            }));
+        }
+
+        if self.is_match(&[TokenType::THIS]) {
+            return Ok(Expr::This(ThisExpr {
+                keyword: self.previous(),
+                depth: Cell::new(None),
+            }));
+        }
+
         if self.is_match(&[TokenType::IDENTIFIER]) {
             return Ok(Expr::Variable(VariableExpr {
                 name: self.previous(),
+                depth: Cell::new(None),
             }));
         }
+        if self.is_match(&[TokenType::LEFTBRACKET]) {
+            return self.list();
+        }
+        // A brace can only start a block at the beginning of a statement,
+        // so in expression position it is always a map literal.
+        if self.is_match(&[TokenType::LEFTBRACE]) {
+            return self.map();
+        }
+
         if self.is_match(&[TokenType::LEFTPAREN]) {
             let expr = self.expression()?;
-            self.consume(TokenType::RIGHTPAREN, ')')?;
+            self.consume(TokenType::RIGHTPAREN, ")")?;
             return Ok(Expr::Grouping(GroupingExpr {
                 expression: Box::new(expr),
             }));
@@ -207,23 +546,88 @@ impl Parser {
         if self.is_match(&[TokenType::PRINT]) {
             return Ok(Expr::Variable(VariableExpr {
                 name: self.previous().clone(),
+                depth: Cell::new(None),
             }));
         }
 
         if self.is_match(&[TokenType::IDENTIFIER]) {
             return Ok(Expr::Variable(VariableExpr {
                 name: self.previous(),
+                depth: Cell::new(None),
             }));
         }
 
-        return Err(SyntaxError::new(
+        Err(SyntaxError::new(
             self.current_tok().line,
             self.current_tok().position,
             SyntaxErrorTypes::UnexpectedToken(self.current_tok().clone().lexeme),
-        ));
+        ))
     }
 
-    fn consume(&mut self, ttype: TokenType, message: char) -> Result<Token, SyntaxError> {
+    /// Parses the elements of a list literal up to the closing bracket.
+    /// A trailing comma after the last element is allowed.
+    fn list(&mut self) -> Result<Expr, SyntaxError> {
+        let mut elements = Vec::new();
+
+        while !self.check(TokenType::RIGHTBRACKET) {
+            elements.push(self.expression()?);
+            if !self.is_match(&[TokenType::COMMA]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RIGHTBRACKET, "]")?;
+        Ok(Expr::List(ListExpr { elements }))
+    }
+
+    /// Parses an interpolated string. The scanner splits `"a ${x} b"` into
+    /// an INTERPOLATION token for each piece of text followed by an
+    /// embedded expression, and a closing STRING token for the rest.
+    fn interpolation(&mut self) -> Result<Expr, SyntaxError> {
+        let mut parts = Vec::new();
+        loop {
+            parts.push(Expr::Literal(LiteralExpr {
+                value: Some(self.previous().literal),
+            }));
+            parts.push(self.expression()?);
+
+            if !self.is_match(&[TokenType::INTERPOLATION]) {
+                break;
+            }
+        }
+
+        let rest = self.consume(TokenType::STRING, "}")?;
+        parts.push(Expr::Literal(LiteralExpr {
+            value: Some(rest.literal),
+        }));
+        Ok(Expr::Interpolation(InterpolationExpr { parts }))
+    }
+
+    /// Parses the `key: value` entries of a map literal up to the closing
+    /// brace. A trailing comma after the last entry is allowed.
+    fn map(&mut self) -> Result<Expr, SyntaxError> {
+        let brace = self.previous();
+        let mut keys = Vec::new();
+        let mut values = Vec::new();
+
+        while !self.check(TokenType::RIGHTBRACE) {
+            keys.push(self.expression()?);
+            self.consume(TokenType::COLON, ":")?;
+            values.push(self.expression()?);
+            if !self.is_match(&[TokenType::COMMA]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RIGHTBRACE, "}")?;
+        Ok(Expr::Map(MapExpr {
+            brace,
+            keys,
+            values,
+        }))
+    }
+
+    fn consume(&mut self, ttype: TokenType, message: &str) -> Result<Token, SyntaxError> {
         if self.check(ttype) {
             Ok(self.advance())
         } else {
@@ -281,65 +685,329 @@ impl Parser {
      */
 
     fn statement(&mut self) -> Result<Stmt, SyntaxError> {
+        if self.is_label() {
+            return self.labelled_statement();
+        }
+        if self.is_match(&[TokenType::BREAK, TokenType::CONTINUE]) {
+            return self.loop_control_statement();
+        }
+        if self.is_match(&[TokenType::FOR]) {
+            return self.for_statement(None);
+        }
+        if self.is_match(&[TokenType::IF]) {
+            return self.if_statement();
+        }
         if self.is_match(&[TokenType::PRINT]) {
             return self.print_statement();
         }
+        if self.is_match(&[TokenType::RETURN]) {
+            return self.return_statement();
+        }
+        if self.is_match(&[TokenType::WHILE]) {
+            return self.while_statement(None);
+        }
+        if self.is_match(&[TokenType::LEFTBRACE]) {
+            return Ok(Stmt::Block(BlockStmt {
+                statements: self.block()?,
+            }));
+        }
         self.expression_statement()
     }
 
-    // fn decleration(&mut self) -> Result<Stmt, SyntaxError> {
-    //     Ok(())
-    //     // if self.is_match(&[TokenType::VAR]) {
-    //     //     self.var_declaration()
-    //     // } else {
-    //     //     self.statement()
-    //     // }
-    //     // .or_else(|_| {
-    //     //     self.synchronize();
-    //     //     Err(SyntaxError::new(
-    //     //         self.current_tok().line,
-    //     //         self.current_tok().position,
-    //     //         SyntaxErrorTypes::UnexpectedToken(self.current_tok().clone().lexeme),
-    //     //     ))
-    //     // Err(SyntaxError::error(
-    //     //     SyntaxErrorTypes::ENEXPECTED_TOKEN,
-    //     //     0,
-    //     //     0,
-    //     //     "Syntax Error".to_string(),
-    //     //     "Error in decleration".to_string(),
-    //     // ))
-    //     // })
-    // }
+    /// Parses the declarations of a block up to and including the
+    /// closing brace. The opening brace has already been consumed.
+    fn block(&mut self) -> Result<Vec<Stmt>, SyntaxError> {
+        let mut statements = Vec::new();
+
+        while !self.check(TokenType::RIGHTBRACE) && !self.is_at_end() {
+            statements.push(self.declaration()?);
+        }
+
+        self.consume(TokenType::RIGHTBRACE, "}")?;
+        Ok(statements)
+    }
+
+    fn declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        if self.is_match(&[TokenType::CLASS]) {
+            return self.class_declaration();
+        }
+        if self.is_match(&[TokenType::FUNC]) {
+            return Ok(Stmt::Function(self.function()?));
+        }
+        if self.is_match(&[TokenType::VAR, TokenType::HAVE]) {
+            return self.var_declaration();
+        }
+        if self.is_short_declaration() {
+            return self.short_declaration();
+        }
+        self.statement()
+    }
 
-    // fn var_declaration(&mut self) -> Result<Stmt, SyntaxError> {
-    //     let name: Token = self.consume(TokenType::IDENTIFIER, 'i').unwrap();
+    /// Methods are written like functions without the `func` keyword.
+    /// A superclass is named after a `<`, as in `class Enemy < Ship`.
+    fn class_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let name = self.consume(TokenType::IDENTIFIER, "class name")?;
 
-    //     let initializer = match self.is_match(&[TokenType::EQUAL]) {
-    //         true => Some(self.expression()?),
-    //         false => None,
-    //     };
+        let superclass = if self.is_match(&[TokenType::LESS]) {
+            Some(Expr::Variable(VariableExpr {
+                name: self.consume(TokenType::IDENTIFIER, "superclass name")?,
+                depth: Cell::new(None),
+            }))
+        } else {
+            None
+        };
 
-    //     self.consume(TokenType::SEMICOLON, ';')?;
+        self.consume(TokenType::LEFTBRACE, "{")?;
 
-    //     return Ok(Stmt::Var(VarStmt {
-    //         name: name,
-    //         initializer,
-    //     }));
-    // }
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RIGHTBRACE) && !self.is_at_end() {
+            methods.push(self.function()?);
+        }
+
+        self.consume(TokenType::RIGHTBRACE, "}")?;
+        Ok(Stmt::Class(ClassStmt {
+            name,
+            superclass,
+            methods,
+        }))
+    }
+
+    fn function(&mut self) -> Result<FunctionStmt, SyntaxError> {
+        let name = self.consume(TokenType::IDENTIFIER, "function name")?;
+
+        self.consume(TokenType::LEFTPAREN, "(")?;
+        let mut params = Vec::new();
+        if !self.check(TokenType::RIGHTPAREN) {
+            params.push(self.consume(TokenType::IDENTIFIER, "parameter name")?);
+            while self.is_match(&[TokenType::COMMA]) {
+                params.push(self.consume(TokenType::IDENTIFIER, "parameter name")?);
+            }
+        }
+        self.consume(TokenType::RIGHTPAREN, ")")?;
+
+        self.consume(TokenType::LEFTBRACE, "{")?;
+        let body = self.block()?;
+
+        Ok(FunctionStmt {
+            name,
+            params: Rc::new(params),
+            body: Rc::new(body),
+        })
+    }
+
+    /// `var` declares a variable and `have` a constant, which has to be
+    /// initialized. Either `=` or `:=` may introduce the initializer.
+    fn var_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let constant = self.previous().ttype == TokenType::HAVE;
+        let name = self.consume(TokenType::IDENTIFIER, "variable name")?;
+
+        let initializer = if self.is_match(&[TokenType::EQUAL, TokenType::COLONEQUAL]) {
+            Some(self.expression()?)
+        } else if constant {
+            return Err(SyntaxError::new(
+                self.current_tok().line,
+                self.current_tok().position,
+                SyntaxErrorTypes::ExpectedToken(":=".to_string(), self.peek().lexeme),
+            ));
+        } else {
+            None
+        };
+
+        self.consume(TokenType::SEMICOLON, ";")?;
+        Ok(Stmt::Var(VarStmt {
+            name,
+            initializer,
+            constant,
+        }))
+    }
+
+    fn is_short_declaration(&self) -> bool {
+        self.check(TokenType::IDENTIFIER)
+            && self
+                .tokens
+                .get(self.current + 1)
+                .is_some_and(|token| token.ttype == TokenType::COLONEQUAL)
+    }
+
+    /// `name := value;` declares a variable without a keyword.
+    fn short_declaration(&mut self) -> Result<Stmt, SyntaxError> {
+        let name = self.advance();
+        self.advance();
+        let initializer = self.expression()?;
+
+        self.consume(TokenType::SEMICOLON, ";")?;
+        Ok(Stmt::Var(VarStmt {
+            name,
+            initializer: Some(initializer),
+            constant: false,
+        }))
+    }
 
     fn current_tok(&mut self) -> &Token {
-        return self.tokens.get(self.current).unwrap();
+        self.tokens.get(self.current).unwrap()
+    }
+
+    /// An `else` binds to the nearest `if`, and `else if` chains fall
+    /// out naturally because the else branch is just another statement.
+    fn if_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        self.consume(TokenType::LEFTPAREN, "(")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RIGHTPAREN, ")")?;
+
+        let then_branch = Box::new(self.statement()?);
+        let else_branch = if self.is_match(&[TokenType::ELSE]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If(IfStmt {
+            condition,
+            then_branch,
+            else_branch,
+        }))
+    }
+
+    /// A label is a name and a colon in front of a loop, as in
+    /// `outer: while (...)`.
+    fn is_label(&self) -> bool {
+        self.check(TokenType::IDENTIFIER)
+            && self
+                .tokens
+                .get(self.current + 1)
+                .is_some_and(|token| token.ttype == TokenType::COLON)
+    }
+
+    fn labelled_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let label = self.advance();
+        self.advance();
+        if self.is_match(&[TokenType::WHILE]) {
+            return self.while_statement(Some(label));
+        }
+        if self.is_match(&[TokenType::FOR]) {
+            return self.for_statement(Some(label));
+        }
+        let p = self.peek();
+        Err(SyntaxError::new(
+            p.line,
+            p.position,
+            SyntaxErrorTypes::ExpectedToken("loop after label".to_string(), p.lexeme),
+        ))
+    }
+
+    fn while_statement(&mut self, label: Option<Token>) -> Result<Stmt, SyntaxError> {
+        self.consume(TokenType::LEFTPAREN, "(")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RIGHTPAREN, ")")?;
+        let body = Box::new(self.statement()?);
+
+        Ok(Stmt::While(WhileStmt {
+            condition,
+            body,
+            increment: None,
+            label,
+        }))
+    }
+
+    /// Desugars `for (init; cond; step) body` into a while loop wrapped
+    /// in a block, so the initializer's variable is scoped to the loop.
+    /// The step is kept on the while node rather than appended to the
+    /// body so it still runs when the body is cut short.
+    fn for_statement(&mut self, label: Option<Token>) -> Result<Stmt, SyntaxError> {
+        self.consume(TokenType::LEFTPAREN, "(")?;
+
+        let initializer = if self.is_match(&[TokenType::SEMICOLON]) {
+            None
+        } else if self.is_match(&[TokenType::VAR, TokenType::HAVE]) {
+            Some(self.var_declaration()?)
+        } else if self.is_short_declaration() {
+            Some(self.short_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(TokenType::SEMICOLON) {
+            Expr::Literal(LiteralExpr {
+                value: Some(Object::Bool(true)),
+            })
+        } else {
+            self.expression()?
+        };
+        self.consume(TokenType::SEMICOLON, ";")?;
+
+        let increment = if self.check(TokenType::RIGHTPAREN) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+        self.consume(TokenType::RIGHTPAREN, ")")?;
+
+        let body = Stmt::While(WhileStmt {
+            condition,
+            body: Box::new(self.statement()?),
+            increment,
+            label,
+        });
+
+        Ok(match initializer {
+            Some(initializer) => Stmt::Block(BlockStmt {
+                statements: vec![initializer, body],
+            }),
+            None => body,
+        })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous();
+        let value = if self.check(TokenType::SEMICOLON) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume(TokenType::SEMICOLON, ";")?;
+        Ok(Stmt::Return(ReturnStmt { keyword, value }))
+    }
+
+    /// `break;` and `continue;` act on the innermost loop, `break outer;`
+    /// on the loop labelled `outer`. The resolver checks both.
+    fn loop_control_statement(&mut self) -> Result<Stmt, SyntaxError> {
+        let keyword = self.previous();
+        let label = if self.check(TokenType::IDENTIFIER) {
+            Some(self.advance())
+        } else {
+            None
+        };
+
+        self.consume(TokenType::SEMICOLON, ";")?;
+        Ok(match keyword.ttype {
+            TokenType::BREAK => Stmt::Break(BreakStmt { keyword, label }),
+            _ => Stmt::Continue(ContinueStmt { keyword, label }),
+        })
     }
 
     fn print_statement(&mut self) -> Result<Stmt, SyntaxError> {
         let value = self.expression()?;
-        self.consume(TokenType::SEMICOLON, ';')?;
+        self.consume(TokenType::SEMICOLON, ";")?;
         Ok(Stmt::Print(PrintStmt { expression: value }))
     }
 
     fn expression_statement(&mut self) -> Result<Stmt, SyntaxError> {
         let expr = self.expression()?;
-        self.consume(TokenType::SEMICOLON, ';')?;
+        self.consume(TokenType::SEMICOLON, ";")?;
         Ok(Stmt::Expression(ExpressionStmt { expression: expr }))
     }
 }
+
+/// Compound assignments and `++`/`--` both read and write their target,
+/// which has to be a variable, a property or an element.
+fn check_place(target: &Expr, operator: &Token) -> Result<(), SyntaxError> {
+    match target {
+        Expr::Variable(_) | Expr::Get(_) | Expr::Index(_) => Ok(()),
+        _ => Err(SyntaxError::new(
+            operator.line,
+            operator.position,
+            SyntaxErrorTypes::InvalidAssignmentTarget(),
+        )),
+    }
+}
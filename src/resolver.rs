@@ -0,0 +1,557 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::errors::syntax_error::SyntaxError;
+use crate::errors::syntax_error::SyntaxErrorTypes;
+use crate::expr::*;
+use crate::stmt::*;
+use crate::tokens::token::*;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// What the resolver knows about a declared name. `constant` holds the
+/// name token of a `have` declaration, so an assignment to it can point
+/// back at where it was declared.
+struct Binding {
+    defined: bool,
+    constant: Option<Token>,
+}
+
+/// A static pass that runs between the parser and the interpreter. It
+/// walks every scope once and records on each variable reference how
+/// many environments out its binding lives, so that closures keep
+/// seeing the variable they captured even when a later declaration
+/// shadows it. References that are not found in any local scope are
+/// left unresolved and looked up in the globals at runtime, but global
+/// constants are still remembered so assigning to them is rejected. A
+/// single resolver is kept across REPL lines for that reason.
+pub struct Resolver {
+    scopes: RefCell<Vec<HashMap<String, Binding>>>,
+    global_constants: RefCell<HashMap<String, Token>>,
+    current_function: Cell<FunctionType>,
+    current_class: Cell<ClassType>,
+    /// The labels of the loops around the code being resolved, innermost
+    /// last. `None` for an unlabelled loop.
+    loops: RefCell<Vec<Option<String>>>,
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver {
+            scopes: RefCell::new(Vec::new()),
+            global_constants: RefCell::new(HashMap::new()),
+            current_function: Cell::new(FunctionType::None),
+            current_class: Cell::new(ClassType::None),
+            loops: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Resolves a whole program, or one REPL line. Scopes left open by an
+    /// error in an earlier run are dropped first.
+    pub fn resolve_program(&self, statements: &[Stmt]) -> Result<(), SyntaxError> {
+        self.scopes.borrow_mut().clear();
+        self.current_function.set(FunctionType::None);
+        self.current_class.set(ClassType::None);
+        self.loops.borrow_mut().clear();
+        self.resolve(statements)
+    }
+
+    pub fn resolve(&self, statements: &[Stmt]) -> Result<(), SyntaxError> {
+        for statement in statements {
+            statement.accept(self)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_expr(&self, expr: &Expr) -> Result<(), SyntaxError> {
+        expr.accept(self)
+    }
+
+    fn begin_scope(&self) {
+        self.scopes.borrow_mut().push(HashMap::new());
+    }
+
+    fn end_scope(&self) {
+        self.scopes.borrow_mut().pop();
+    }
+
+    /// Adds the name to the innermost scope, marked as not ready yet so
+    /// that a variable referring to itself in its initializer is caught.
+    /// Globals may be redeclared, unless the earlier declaration was a
+    /// constant.
+    fn declare(&self, name: &Token, constant: bool) -> Result<(), SyntaxError> {
+        let already_declared = match self.scopes.borrow_mut().last_mut() {
+            Some(scope) => {
+                let already_declared = scope.contains_key(&name.lexeme);
+                let constant = constant.then(|| name.clone());
+                scope.insert(
+                    name.lexeme.clone(),
+                    Binding {
+                        defined: false,
+                        constant,
+                    },
+                );
+                already_declared
+            }
+            None => {
+                let mut global_constants = self.global_constants.borrow_mut();
+                let already_declared = global_constants.contains_key(&name.lexeme);
+                if constant {
+                    global_constants.insert(name.lexeme.clone(), name.clone());
+                }
+                already_declared
+            }
+        };
+
+        if already_declared {
+            return Err(SyntaxError::new(
+                name.line,
+                name.position,
+                SyntaxErrorTypes::AlreadyDeclared(name.lexeme.clone()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn define(&self, name: &Token) {
+        if let Some(scope) = self.scopes.borrow_mut().last_mut() {
+            if let Some(binding) = scope.get_mut(&name.lexeme) {
+                binding.defined = true;
+            }
+        }
+    }
+
+    /// Rejects an assignment to a name that the innermost declaration
+    /// visible from here made a constant.
+    fn check_assignable(&self, name: &Token) -> Result<(), SyntaxError> {
+        let scopes = self.scopes.borrow();
+        let declaration = match scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.lexeme))
+        {
+            Some(binding) => binding.constant.clone(),
+            None => self.global_constants.borrow().get(&name.lexeme).cloned(),
+        };
+
+        match declaration {
+            Some(declaration) => Err(SyntaxError::new(
+                name.line,
+                name.position,
+                SyntaxErrorTypes::AssignToConstant(
+                    name.lexeme.clone(),
+                    declaration.line,
+                    declaration.position,
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolves the place a compound assignment or `++`/`--` writes to.
+    /// A variable target must not be a constant; resolving it as an
+    /// expression then records its depth for both the read and the write.
+    fn resolve_target(&self, target: &Expr) -> Result<(), SyntaxError> {
+        if let Expr::Variable(variable) = target {
+            self.check_assignable(&variable.name)?;
+        }
+        self.resolve_expr(target)
+    }
+
+    /// Records the distance from the innermost scope to the one that
+    /// declares the name. Globals are not tracked and stay unresolved.
+    fn resolve_local(&self, name: &Token, depth: &Cell<Option<usize>>) {
+        for (distance, scope) in self.scopes.borrow().iter().rev().enumerate() {
+            if scope.contains_key(&name.lexeme) {
+                depth.set(Some(distance));
+                return;
+            }
+        }
+    }
+
+    fn resolve_function(
+        &self,
+        function: &FunctionStmt,
+        function_type: FunctionType,
+    ) -> Result<(), SyntaxError> {
+        let enclosing_function = self.current_function.replace(function_type);
+        // A loop around the declaration cannot be left from inside the body.
+        let enclosing_loops = self.loops.take();
+
+        self.begin_scope();
+        let result = function
+            .params
+            .iter()
+            .try_for_each(|param| {
+                self.declare(param, false)?;
+                self.define(param);
+                Ok(())
+            })
+            .and_then(|_| self.resolve(&function.body));
+        self.end_scope();
+
+        self.current_function.set(enclosing_function);
+        self.loops.replace(enclosing_loops);
+        result
+    }
+
+    /// `break` and `continue` must sit inside a loop, and a label they
+    /// name must belong to one of the loops around them.
+    fn check_loop_control(
+        &self,
+        keyword: &Token,
+        label: &Option<Token>,
+    ) -> Result<(), SyntaxError> {
+        let loops = self.loops.borrow();
+        if loops.is_empty() {
+            return Err(SyntaxError::new(
+                keyword.line,
+                keyword.position,
+                SyntaxErrorTypes::OutsideLoop(keyword.lexeme.clone()),
+            ));
+        }
+
+        match label {
+            Some(label)
+                if !loops
+                    .iter()
+                    .any(|name| name.as_deref() == Some(label.lexeme.as_str())) =>
+            {
+                Err(SyntaxError::new(
+                    label.line,
+                    label.position,
+                    SyntaxErrorTypes::UndefinedLabel(label.lexeme.clone()),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn resolve_class(&self, class: &ClassStmt) -> Result<(), SyntaxError> {
+        self.declare(&class.name, false)?;
+        self.define(&class.name);
+
+        if let Some(superclass) = &class.superclass {
+            if let Expr::Variable(variable) = superclass {
+                if variable.name.lexeme == class.name.lexeme {
+                    return Err(SyntaxError::new(
+                        variable.name.line,
+                        variable.name.position,
+                        SyntaxErrorTypes::InheritFromSelf(class.name.lexeme.clone()),
+                    ));
+                }
+            }
+
+            self.current_class.set(ClassType::Subclass);
+            self.resolve_expr(superclass)?;
+
+            self.begin_scope();
+            self.define_name("super");
+        }
+
+        self.begin_scope();
+        self.define_name("this");
+
+        let result = class.methods.iter().try_for_each(|method| {
+            let function_type = if method.name.lexeme == "init" {
+                FunctionType::Initializer
+            } else {
+                FunctionType::Method
+            };
+            self.resolve_function(method, function_type)
+        });
+
+        self.end_scope();
+        if class.superclass.is_some() {
+            self.end_scope();
+        }
+        result
+    }
+
+    /// Defines a name the language itself introduces, like `this`, in the
+    /// innermost scope.
+    fn define_name(&self, name: &str) {
+        if let Some(scope) = self.scopes.borrow_mut().last_mut() {
+            scope.insert(
+                name.to_string(),
+                Binding {
+                    defined: true,
+                    constant: None,
+                },
+            );
+        }
+    }
+}
+
+impl StmtVisitor<()> for Resolver {
+    fn visit_block_stmt(&self, expr: &BlockStmt) -> Result<(), SyntaxError> {
+        self.begin_scope();
+        let result = self.resolve(&expr.statements);
+        self.end_scope();
+        result
+    }
+
+    fn visit_break_stmt(&self, expr: &BreakStmt) -> Result<(), SyntaxError> {
+        self.check_loop_control(&expr.keyword, &expr.label)
+    }
+
+    /// Methods are resolved inside an extra scope holding `this`, which
+    /// mirrors the environment `CedarFunction::bind` creates at runtime.
+    /// Subclasses get one more scope outside that for `super`.
+    fn visit_class_stmt(&self, expr: &ClassStmt) -> Result<(), SyntaxError> {
+        let enclosing_class = self.current_class.replace(ClassType::Class);
+        let result = self.resolve_class(expr);
+        self.current_class.set(enclosing_class);
+        result
+    }
+
+    fn visit_continue_stmt(&self, expr: &ContinueStmt) -> Result<(), SyntaxError> {
+        self.check_loop_control(&expr.keyword, &expr.label)
+    }
+
+    fn visit_expression_stmt(&self, expr: &ExpressionStmt) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.expression)
+    }
+
+    fn visit_function_stmt(&self, expr: &FunctionStmt) -> Result<(), SyntaxError> {
+        self.declare(&expr.name, false)?;
+        self.define(&expr.name);
+        self.resolve_function(expr, FunctionType::Function)
+    }
+
+    fn visit_if_stmt(&self, expr: &IfStmt) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.condition)?;
+        expr.then_branch.accept(self)?;
+        if let Some(else_branch) = &expr.else_branch {
+            else_branch.accept(self)?;
+        }
+        Ok(())
+    }
+
+    fn visit_print_stmt(&self, expr: &PrintStmt) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.expression)
+    }
+
+    fn visit_return_stmt(&self, expr: &ReturnStmt) -> Result<(), SyntaxError> {
+        if self.current_function.get() == FunctionType::None {
+            return Err(SyntaxError::new(
+                expr.keyword.line,
+                expr.keyword.position,
+                SyntaxErrorTypes::TopLevelReturn(),
+            ));
+        }
+
+        if let Some(value) = &expr.value {
+            if self.current_function.get() == FunctionType::Initializer {
+                return Err(SyntaxError::new(
+                    expr.keyword.line,
+                    expr.keyword.position,
+                    SyntaxErrorTypes::ReturnFromInitializer(),
+                ));
+            }
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_var_stmt(&self, expr: &VarStmt) -> Result<(), SyntaxError> {
+        self.declare(&expr.name, expr.constant)?;
+        if let Some(initializer) = &expr.initializer {
+            self.resolve_expr(initializer)?;
+        }
+        self.define(&expr.name);
+        Ok(())
+    }
+
+    fn visit_while_stmt(&self, expr: &WhileStmt) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.condition)?;
+        self.loops
+            .borrow_mut()
+            .push(expr.label.as_ref().map(|label| label.lexeme.clone()));
+        let result = expr.body.accept(self);
+        self.loops.borrow_mut().pop();
+        result?;
+        if let Some(increment) = &expr.increment {
+            self.resolve_expr(increment)?;
+        }
+        Ok(())
+    }
+}
+
+impl ExprVisitor<()> for Resolver {
+    fn visit_assign_expr(&self, expr: &AssignExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.value)?;
+        self.check_assignable(&expr.name)?;
+        self.resolve_local(&expr.name, &expr.depth);
+        Ok(())
+    }
+
+    fn visit_binary_expr(&self, expr: &BinaryExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.left)?;
+        self.resolve_expr(&expr.right)
+    }
+
+    fn visit_call_expr(&self, expr: &CallExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.callee)?;
+        for argument in &expr.arguments {
+            self.resolve_expr(argument)?;
+        }
+        Ok(())
+    }
+
+    fn visit_compound_expr(&self, expr: &CompoundExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.value)?;
+        self.resolve_target(&expr.target)
+    }
+
+    fn visit_conditional_expr(&self, expr: &ConditionalExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.condition)?;
+        self.resolve_expr(&expr.then_branch)?;
+        self.resolve_expr(&expr.else_branch)
+    }
+
+    fn visit_get_expr(&self, expr: &GetExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.object)
+    }
+
+    fn visit_grouping_expr(&self, expr: &GroupingExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.expression)
+    }
+
+    fn visit_index_expr(&self, expr: &IndexExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.object)?;
+        self.resolve_expr(&expr.index)
+    }
+
+    fn visit_interpolation_expr(&self, expr: &InterpolationExpr) -> Result<(), SyntaxError> {
+        for part in &expr.parts {
+            self.resolve_expr(part)?;
+        }
+        Ok(())
+    }
+
+    fn visit_list_expr(&self, expr: &ListExpr) -> Result<(), SyntaxError> {
+        for element in &expr.elements {
+            self.resolve_expr(element)?;
+        }
+        Ok(())
+    }
+
+    fn visit_literal_expr(&self, _expr: &LiteralExpr) -> Result<(), SyntaxError> {
+        Ok(())
+    }
+
+    fn visit_logical_expr(&self, expr: &LogicalExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.left)?;
+        self.resolve_expr(&expr.right)
+    }
+
+    fn visit_map_expr(&self, expr: &MapExpr) -> Result<(), SyntaxError> {
+        for (key, value) in expr.keys.iter().zip(&expr.values) {
+            self.resolve_expr(key)?;
+            self.resolve_expr(value)?;
+        }
+        Ok(())
+    }
+
+    fn visit_safechain_expr(&self, expr: &SafeChainExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.expression)
+    }
+
+    fn visit_set_expr(&self, expr: &SetExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.value)?;
+        self.resolve_expr(&expr.object)
+    }
+
+    fn visit_setindex_expr(&self, expr: &SetIndexExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.value)?;
+        self.resolve_expr(&expr.object)?;
+        self.resolve_expr(&expr.index)
+    }
+
+    fn visit_slice_expr(&self, expr: &SliceExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.object)?;
+        if let Some(start) = &expr.start {
+            self.resolve_expr(start)?;
+        }
+        if let Some(end) = &expr.end {
+            self.resolve_expr(end)?;
+        }
+        Ok(())
+    }
+
+    fn visit_super_expr(&self, expr: &SuperExpr) -> Result<(), SyntaxError> {
+        match self.current_class.get() {
+            ClassType::Subclass => {}
+            ClassType::None => {
+                return Err(SyntaxError::new(
+                    expr.keyword.line,
+                    expr.keyword.position,
+                    SyntaxErrorTypes::SuperOutsideClass(),
+                ))
+            }
+            ClassType::Class => {
+                return Err(SyntaxError::new(
+                    expr.keyword.line,
+                    expr.keyword.position,
+                    SyntaxErrorTypes::SuperWithoutSuperclass(),
+                ))
+            }
+        }
+
+        self.resolve_local(&expr.keyword, &expr.depth);
+        Ok(())
+    }
+
+    fn visit_this_expr(&self, expr: &ThisExpr) -> Result<(), SyntaxError> {
+        if self.current_class.get() == ClassType::None {
+            return Err(SyntaxError::new(
+                expr.keyword.line,
+                expr.keyword.position,
+                SyntaxErrorTypes::ThisOutsideClass(),
+            ));
+        }
+
+        self.resolve_local(&expr.keyword, &expr.depth);
+        Ok(())
+    }
+
+    fn visit_unary_expr(&self, expr: &UnaryExpr) -> Result<(), SyntaxError> {
+        self.resolve_expr(&expr.right)
+    }
+
+    fn visit_update_expr(&self, expr: &UpdateExpr) -> Result<(), SyntaxError> {
+        self.resolve_target(&expr.target)
+    }
+
+    fn visit_variable_expr(&self, expr: &VariableExpr) -> Result<(), SyntaxError> {
+        if let Some(scope) = self.scopes.borrow().last() {
+            if scope
+                .get(&expr.name.lexeme)
+                .is_some_and(|binding| !binding.defined)
+            {
+                return Err(SyntaxError::new(
+                    expr.name.line,
+                    expr.name.position,
+                    SyntaxErrorTypes::ReadInOwnInitializer(expr.name.lexeme.clone()),
+                ));
+            }
+        }
+
+        self.resolve_local(&expr.name, &expr.depth);
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@ use crate::object::*;
 use crate::tokens::token::*;
 // use crate::tokens::token_type;
 use crate::tokens::token_type::*;
+use unicode_width::UnicodeWidthChar;
+use unicode_xid::UnicodeXID;
 pub struct Scanner {
     source: Vec<char>,
     tokens: Vec<Token>,
@@ -12,19 +14,36 @@ pub struct Scanner {
     line: usize,
     file_name: String,
     current: usize,
+    /// The line and display column `current` is at, recorded with it so
+    /// a token is located by its first character.
+    start: (usize, usize),
+    interpolations: Vec<StringLiteral>,
+    tab_width: usize,
+    byte_mark: (usize, usize),
 }
 
 impl Scanner {
     pub fn new(source: Vec<char>, file_name: String) -> Self {
-        return Self {
+        Self {
             source,
             tokens: Vec::new(),
             position: 0,
             line: 0,
             offset: 0,
             current: 0,
+            start: (0, 0),
             file_name,
-        };
+            interpolations: Vec::new(),
+            tab_width: 4,
+            byte_mark: (0, 0),
+        }
+    }
+
+    /// Sets how many columns a tab advances to, so reported columns match
+    /// what an editor shows. Defaults to 4.
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width.max(1);
+        self
     }
 
     /// Scans the source code and generates a vector of tokens. It
@@ -35,23 +54,19 @@ impl Scanner {
     /// function returns a reference to the vector of tokens, wrapped
     /// in a Result indicating success or an error of type LexerError.
     pub fn scan_tokens(&mut self) -> Result<&Vec<Token>, LexerError> {
+        self.skip_preamble();
         while !self.is_eof() {
             match self.scan_token() {
                 Ok(_) => {}
                 Err(e) => {
-                    e.report();
+                    e.report(self.tab_width);
                     break;
                 }
             }
         }
 
-        self.tokens.push(Token::new(
-            TokenType::EOF,
-            "".to_string(),
-            Object::Nil,
-            self.line,
-            self.offset,
-        ));
+        self.mark_start();
+        self.push_token(TokenType::EOF, "".to_string(), Object::Nil);
         Ok(&self.tokens)
     }
 
@@ -61,7 +76,7 @@ impl Scanner {
     ///  of the source code, it is considered to have reached the end
     /// and returns true, otherwise it returns false.
     fn is_eof(&mut self) -> bool {
-        return self.position >= self.source.len();
+        self.position >= self.source.len()
     }
 
     /// Returns the current character at the current position in the
@@ -69,9 +84,9 @@ impl Scanner {
     /// the source string, a space character is returned instead.
     fn at(&mut self) -> char {
         if self.position != self.source.len() {
-            return self.source[self.position];
+            self.source[self.position]
         } else {
-            return ' ';
+            ' '
         }
     }
 
@@ -90,13 +105,7 @@ impl Scanner {
     /// Token::new() method and pushed to the end of the tokens vector.
     fn add_token(&mut self, tok_type: TokenType) {
         let value = self.next();
-        self.tokens.push(Token::new(
-            tok_type,
-            value.unwrap().to_string(),
-            Object::Nil,
-            self.line,
-            self.offset,
-        ))
+        self.push_token(tok_type, value.unwrap().to_string(), Object::Nil);
     }
 
     /// Returns true if the next character in the source matches the
@@ -133,99 +142,260 @@ impl Scanner {
         if self.peek(compare) {
             let value = format!(
                 "{}{}",
-                self.next().unwrap_or('\0').to_string(),
-                self.next().unwrap_or('\0').to_string()
+                self.next().unwrap_or('\0'),
+                self.next().unwrap_or('\0')
             );
-            self.tokens.push(Token::new(
-                type_true,
-                value,
-                Object::Nil,
-                self.line,
-                self.offset,
-            ))
+            self.push_token(type_true, value, Object::Nil);
         } else {
-            match self.next() {
-                Some(ch) => self.tokens.push(Token::new(
-                    type_false,
-                    ch.to_string(),
-                    Object::Nil,
-                    self.line,
-                    self.offset,
-                )),
-                None => (),
+            if let Some(ch) = self.next() {
+                self.push_token(type_false, ch.to_string(), Object::Nil);
             }
         }
     }
 
     /// This function checks for comments in the input and updates
-    /// the tokenizer state accordingly, handling single-line and  
-    /// comments and multi-line comments.
-    fn check_for_comments(&mut self) {
-        //Check for multi line
-        //TODO: Add multiline comments
-        if self.peek('-') {
-            self.next();
+    /// the tokenizer state accordingly, handling `//` line comments,
+    /// `///` doc comments and nestable `/* */` block comments. A lone
+    /// slash is a SLASH token. The newline ending a line comment is left
+    /// for the main loop so line counting stays in one place.
+    fn check_for_comments(&mut self) -> Result<(), LexerError> {
+        if self.peek('*') {
+            return self.block_comment();
+        }
+        if !self.peek('/') {
+            self.add_conditional_token('=', TokenType::SLASHEQUAL, TokenType::SLASH);
+            return Ok(());
+        }
+
+        let is_doc = self.source.get(self.position + 2) == Some(&'/')
+            && self.source.get(self.position + 3) != Some(&'/');
+        while !self.is_eof() && self.at() != '\n' && self.at() != '\r' {
             self.next();
-            while !self.peek('\n') && !self.is_eof() {
+        }
+
+        // Doc comments are kept for tooling; the parser skips them.
+        if is_doc {
+            let text: String = self.source[self.current + 3..self.position]
+                .iter()
+                .collect();
+            let text = text
+                .strip_prefix(' ')
+                .unwrap_or(&text)
+                .trim_end()
+                .to_string();
+            self.add_string_token(Object::Str(text.clone()), TokenType::DOCCOMMENT, text);
+        }
+        Ok(())
+    }
+
+    /// Skips a `/* */` comment, which may span lines and contain nested
+    /// block comments. If the input ends first, it returns a Lexer Error
+    /// pointing at the opening `/*`.
+    fn block_comment(&mut self) -> Result<(), LexerError> {
+        let (line, offset) = (self.line, self.offset);
+        let mut depth = 0;
+        while !self.is_eof() {
+            if self.at() == '/' && self.peek('*') {
+                self.next();
+                self.next();
+                depth += 1;
+            } else if self.at() == '*' && self.peek('/') {
+                self.next();
+                self.next();
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            } else if self.at() == '\n' {
+                self.next_line();
+            } else if self.at() == '\r' {
+                self.carriage_return();
+            } else {
                 self.next();
             }
-            self.next_line();
         }
 
-        if self.peek('/') {
-            self.next();
-            self.next();
-            while !self.peek('\n') && !self.is_eof() {
+        Err(LexerError::new(
+            line,
+            offset,
+            LexerErrorTypes::UnterminatedComment,
+            self.file_name.clone(),
+            self.source.clone(),
+        ))
+    }
+
+    /// Skips a UTF-8 byte order mark and a `#!` shebang line at the very
+    /// start of the source. The newline ending the shebang is left for
+    /// the main loop.
+    fn skip_preamble(&mut self) {
+        if self.source.first() == Some(&'\u{FEFF}') {
+            self.position += 1;
+        }
+        if self.source[self.position..].starts_with(&['#', '!']) {
+            while !self.is_eof() && self.at() != '\n' && self.at() != '\r' {
                 self.next();
             }
-            self.next_line();
-        } else {
-            self.add_token(TokenType::SLASH);
         }
     }
 
-    /// Increments the current position and offset of the scanner by 1,
-    /// effectively advancing to the next character without doing
-    /// anything else. It is typically used to skip over whitespace or
-    /// other non-significant characters.
-    fn empty_next(&mut self) {
-        self.position += 1;
-        self.offset += 1;
+    /// Skips a carriage return. In a CRLF pair the line feed that follows
+    /// does the line counting; a lone carriage return is a line break of
+    /// its own.
+    fn carriage_return(&mut self) {
+        if self.peek('\n') {
+            self.position += 1;
+        } else {
+            self.next_line();
+        }
     }
 
     /// This function extracts a string token from the input and updates
-    /// the tokenizer state accordingly. It scans the input for the end
-    /// of the string while handling newline characters appropriately.
-    /// If the end of the input is reached before the end of the string
-    /// is found, it returns a Lexer Error. If the string is successfully
-    /// parsed, a string token is added to the tokenizer state.
-    // TODO: Add check for new line
-    fn string(&mut self) -> Result<(), LexerError> {
-        self.next();
-        while !self.peek('"') && !self.is_eof() {
+    /// the tokenizer state accordingly. The scanner is positioned on the
+    /// opening quote; `"""` opens a multi-line string whose common
+    /// indentation is stripped.
+    fn string(&mut self, raw: bool) -> Result<(), LexerError> {
+        let (line, offset) = (self.line, self.offset);
+        let triple = self.peek('"') && self.source.get(self.position + 2) == Some(&'"');
+        let quotes = if triple { 3 } else { 1 };
+        for _ in 0..quotes {
             self.next();
-            if self.peek('\n') {
-                self.next_line();
+        }
+
+        self.string_body(StringLiteral::new(raw, triple, line, offset))
+    }
+
+    /// Scans string contents up to the closing quote. Escape sequences
+    /// are decoded unless the string is raw (`r"..."`). A `${` hands
+    /// control back to the normal scanner for the embedded expression:
+    /// an INTERPOLATION token is emitted for the text so far and the
+    /// literal waits on the interpolation stack until the matching `}`.
+    /// If the end of the input is reached before the closing quote, it
+    /// returns a Lexer Error pointing at the start of the string.
+    fn string_body(&mut self, mut string: StringLiteral) -> Result<(), LexerError> {
+        let quotes = if string.triple { 3 } else { 1 };
+        loop {
+            if self.is_eof() {
+                return Err(self.unterminated_string(&string));
+            }
+
+            let c = self.at();
+            if c == '"' && (!string.triple || self.source[self.position..].starts_with(&['"'; 3])) {
+                for _ in 0..quotes {
+                    self.next();
+                }
+                break;
+            }
+
+            match c {
+                '\r' if self.peek('\n') => self.position += 1,
+                '\n' | '\r' => {
+                    self.next_line();
+                    string.lines.push((String::new(), 0));
+                }
+                '\\' if !string.raw => {
+                    let decoded = self.escape()?;
+                    string.push(decoded, false);
+                }
+                '$' if !string.raw && self.peek('{') => {
+                    self.next();
+                    self.next();
+                    string.start_hole(self.tokens.len());
+                    self.add_string_token(Object::Nil, TokenType::INTERPOLATION, String::new());
+                    self.interpolations.push(string);
+                    return Ok(());
+                }
+                _ => {
+                    string.push(c, true);
+                    self.next();
+                }
             }
         }
-        if self.is_eof() {
-            return Err(LexerError::new(
-                self.line,
-                self.offset,
-                LexerErrorTypes::UnexpectedEndOfString,
+
+        // Only now is the whole literal known, so the text of the
+        // INTERPOLATION tokens emitted along the way is filled in here.
+        let tokens = std::mem::take(&mut string.tokens);
+        let mut parts = string.into_parts();
+        let last = parts.pop().unwrap_or_default();
+        for (index, part) in tokens.into_iter().zip(parts) {
+            self.tokens[index].literal = Object::Str(part.clone());
+            self.tokens[index].lexeme = part;
+        }
+        self.add_string_token(Object::Str(last.clone()), TokenType::STRING, last);
+        Ok(())
+    }
+
+    fn unterminated_string(&self, string: &StringLiteral) -> LexerError {
+        LexerError::new(
+            string.line,
+            string.offset,
+            LexerErrorTypes::UnexpectedEndOfString,
+            self.file_name.clone(),
+            self.source.clone(),
+        )
+    }
+
+    /// Decodes the escape sequence starting at the backslash under the
+    /// scanner. Supports `\n`, `\t`, `\r`, `\0`, `\\`, `\"`, `\'`, `\$` and
+    /// `\u{...}` with one to six hex digits.
+    fn escape(&mut self) -> Result<char, LexerError> {
+        let (start, line, offset) = (self.position, self.line, self.offset);
+        self.next();
+
+        let decoded = if self.is_eof() || self.at() == '\n' {
+            None
+        } else {
+            match self.next() {
+                Some('n') => Some('\n'),
+                Some('t') => Some('\t'),
+                Some('r') => Some('\r'),
+                Some('0') => Some('\0'),
+                Some('\\') => Some('\\'),
+                Some('"') => Some('"'),
+                Some('\'') => Some('\''),
+                Some('$') => Some('$'),
+                Some('u') => self.unicode_escape(),
+                _ => None,
+            }
+        };
+
+        match decoded {
+            Some(c) => Ok(c),
+            None => Err(LexerError::new(
+                line,
+                offset,
+                LexerErrorTypes::InvalidEscape(self.source[start..self.position].iter().collect()),
                 self.file_name.clone(),
                 self.source.clone(),
-            ));
+            )),
         }
+    }
 
+    /// Reads the `{XXXX}` part of a `\u{XXXX}` escape. Returns None if the
+    /// braces are missing, there are no or more than six hex digits, or
+    /// the value is not a Unicode scalar value.
+    fn unicode_escape(&mut self) -> Option<char> {
+        if self.at() != '{' {
+            return None;
+        }
         self.next();
+
+        let start = self.position;
+        while !self.is_eof() && self.at().is_ascii_hexdigit() {
+            self.next();
+        }
+        let digits: String = self.source[start..self.position].iter().collect();
+
+        if self.is_eof() || self.at() != '}' {
+            return None;
+        }
         self.next();
 
-        let value: String = self.source[self.current + 1..self.position - 1]
-            .iter()
-            .collect();
-        self.add_string_token(Object::Str(value.clone()), TokenType::STRING, value);
-        Ok(())
+        if digits.is_empty() || digits.len() > 6 {
+            return None;
+        }
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
     }
 
     /// This function adds a new string token to the tokenizer state with
@@ -234,96 +404,197 @@ impl Scanner {
     /// tokenizer state, updating the line and current position information
     /// as appropriate.
     fn add_string_token(&mut self, object_type: Object, tok_type: TokenType, value: String) {
+        self.push_token(tok_type, value, object_type);
+    }
+
+    /// Starts a new token at the character under the scanner.
+    fn mark_start(&mut self) {
+        self.current = self.position;
+        self.start = (self.line, self.offset);
+    }
+
+    /// Pushes a token that started at `current`, recording its byte
+    /// offset in the source alongside the line and display column.
+    fn push_token(&mut self, tok_type: TokenType, lexeme: String, literal: Object) {
+        // Tokens arrive in source order, so the byte count carries on from
+        // the previous token instead of starting over.
+        let (mut index, mut byte_offset) = self.byte_mark;
+        if index > self.current {
+            (index, byte_offset) = (0, 0);
+        }
+        byte_offset += self.source[index..self.current]
+            .iter()
+            .map(|c| c.len_utf8())
+            .sum::<usize>();
+        self.byte_mark = (self.current, byte_offset);
         self.tokens.push(Token::new(
             tok_type,
-            value,
-            object_type,
-            self.line,
-            self.offset,
+            lexeme,
+            literal,
+            self.start.0,
+            self.start.1 + 1,
+            byte_offset,
         ))
     }
 
     /// Extracts a number token from the input and updates the
-    /// tokenizer state accordingly. It scans the input for the end of the
-    /// number, handling floating-point values if present. If the number
-    /// is successfully parsed, a number token is added to the tokenizer
-    /// state with the appropriate object type (Num) and token type (NUMBER).
-    /// If an invalid number is encountered, it returns a Lexer Error.
+    /// tokenizer state accordingly. Besides plain decimals it accepts
+    /// `0x`, `0o` and `0b` prefixed ints, `_` separators between digits,
+    /// an exponent (`6.02e23`), and a fraction without an integer part
+    /// (`.5`) or without digits (`1.`). The value is an Int unless there
+    /// is a fraction or exponent. If the literal is malformed, such as a
+    /// digit outside its base or an int too large for 64 bits, it returns
+    /// a Lexer Error pointing at the offending character.
     fn number(&mut self) -> Result<(), LexerError> {
-        while {
-            let next = self.peak_next();
-            self.is_digit(next)
-        } {
-            self.next();
-        }
+        let radix = match (self.at(), self.peak_next()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'o' | 'O') => 8,
+            ('0', 'b' | 'B') => 2,
+            _ => 10,
+        };
 
-        //Look for floating point
-        if self.peek('.') {
+        let mut is_float = false;
+        if radix != 10 {
             self.next();
+            self.next();
+            if self.digits(radix)? == 0 {
+                return Err(self.number_error(format!(
+                    "Expected {} digits after '0{}'.",
+                    radix_name(radix),
+                    self.source[self.current + 1]
+                )));
+            }
+            if self.at() == '.' && self.peak_next().is_ascii_digit() {
+                return Err(self.number_error(format!(
+                    "A {} number cannot have a fraction.",
+                    radix_name(radix)
+                )));
+            }
+        } else {
+            self.digits(10)?;
+
+            // `1.foo` is left as a number followed by a property access.
             let next = self.peak_next();
-            if self.is_digit(next) {
+            if self.at() == '.' && !self.is_alpha(next) {
+                is_float = true;
                 self.next();
-                while {
-                    let next = self.peak_next();
-                    self.is_digit(next)
-                } {
+                self.digits(10)?;
+            }
+
+            if self.at() == 'e' || self.at() == 'E' {
+                is_float = true;
+                self.next();
+                if self.at() == '+' || self.at() == '-' {
                     self.next();
                 }
-            } else {
-                return Err(LexerError::new(
-                    self.line,
-                    self.offset,
-                    LexerErrorTypes::InvalidFloatingPoint(),
-                    self.file_name.clone(),
-                    self.source.clone(),
-                ));
+                if self.digits(10)? == 0 {
+                    return Err(
+                        self.number_error("Expected digits after the exponent.".to_string())
+                    );
+                }
             }
         }
 
-        self.next();
-        let value: String = self.source[self.current..self.position].iter().collect();
-        let number = match value.parse::<f64>() {
-            Ok(num) => num,
-            Err(_) => {
-                return Err(LexerError::new(
-                    self.line,
-                    self.offset,
-                    LexerErrorTypes::InvalidNumber(value),
-                    self.file_name.clone(),
-                    self.source.clone(),
-                ));
-            }
+        let c = self.at();
+        if !self.is_eof() && (c.is_alphanumeric() || c == '_') {
+            return Err(self.number_error(format!(
+                "'{}' is not a valid digit in a {} number.",
+                c,
+                radix_name(radix)
+            )));
+        }
+
+        let text: String = self.source[self.current..self.position].iter().collect();
+        let digits: String = text.chars().filter(|&c| c != '_').collect();
+        let number = if is_float {
+            digits
+                .parse::<f64>()
+                .ok()
+                .filter(|x| x.is_finite())
+                .map(Object::Float)
+        } else if radix == 10 {
+            digits.parse::<i64>().ok().map(Object::Int)
+        } else {
+            i64::from_str_radix(&digits[2..], radix)
+                .ok()
+                .map(Object::Int)
         };
-        self.add_string_token(
-            Object::Num(number),
-            TokenType::NUMBER,
-            self.source[self.current..self.position].iter().collect(),
-        );
-        Ok(())
+
+        match number {
+            Some(number) => {
+                self.add_string_token(number, TokenType::NUMBER, text);
+                Ok(())
+            }
+            None => {
+                // Point at the start of the literal rather than its end.
+                self.offset -= self.position - self.current;
+                self.position = self.current;
+                Err(self.number_error("Number is too large.".to_string()))
+            }
+        }
+    }
+
+    /// Consumes a run of digits in the given radix, allowing single
+    /// underscores between digits as separators, and returns how many
+    /// digits were read. Stops at an exponent marker in decimal numbers.
+    fn digits(&mut self, radix: u32) -> Result<usize, LexerError> {
+        let mut count = 0;
+        while !self.is_eof() {
+            let c = self.at();
+            if c == '_' {
+                if count == 0 || !self.peak_next().is_digit(radix) {
+                    return Err(
+                        self.number_error("Underscores must sit between digits.".to_string())
+                    );
+                }
+            } else if radix == 10 && (c == 'e' || c == 'E') {
+                break;
+            } else if c.is_digit(radix) {
+                count += 1;
+            } else if c.is_alphanumeric() {
+                return Err(self.number_error(format!(
+                    "'{}' is not a valid digit in a {} number.",
+                    c,
+                    radix_name(radix)
+                )));
+            } else {
+                break;
+            }
+            self.next();
+        }
+        Ok(count)
+    }
+
+    /// An InvalidNumber error pointing at the character under the
+    /// scanner.
+    fn number_error(&self, problem: String) -> LexerError {
+        LexerError::new(
+            self.line,
+            self.offset,
+            LexerErrorTypes::InvalidNumber(problem),
+            self.file_name.clone(),
+            self.source.clone(),
+        )
     }
 
     /// This function checks if a character is a digit (0-9) and returns a
     /// boolean value accordingly.
     fn is_digit(&mut self, char: char) -> bool {
-        return char >= '0' && char <= '9';
+        char.is_ascii_digit()
     }
 
-    /// This function checks if a character is an alphabetic character,
-    /// underscore (_), vertical bar (|), or ampersand (&), and returns
-    /// a boolean value accordingly.
+    /// This function checks if a character can start an identifier: an
+    /// underscore or any Unicode XID_Start character, so `café` and
+    /// `変数` are valid names.
     fn is_alpha(&mut self, c: char) -> bool {
-        return (c >= 'a' && c <= 'z')
-            || (c >= 'A' && c <= 'Z')
-            || c == '_'
-            || c == '|'
-            || c == '&';
+        c == '_' || c.is_xid_start()
     }
 
-    /// This function checks if a character is either an alphabetic
-    /// character or a digit (0-9), using the is_alpha and is_digit
-    /// helper functions, and returns a boolean value accordingly.
+    /// This function checks if a character can continue an identifier,
+    /// which adds digits and combining marks (Unicode XID_Continue) to
+    /// the characters accepted by is_alpha.
     fn is_alpha_numeric(&mut self, c: char) -> bool {
-        return self.is_alpha(c) || self.is_digit(c);
+        c.is_xid_continue()
     }
 
     /// This function scans an identifier by repeatedly advancing
@@ -370,41 +641,93 @@ impl Scanner {
     fn scan_token(&mut self) -> Result<(), LexerError> {
         while !self.is_eof() {
             let c = self.at();
-            self.current = self.position;
+            self.mark_start();
 
             match c {
                 '\n' => self.next_line(),
-                ' ' => self.empty_next(),
+                '\r' => self.carriage_return(),
+                c if c.is_whitespace() => {
+                    self.next();
+                }
                 '(' => self.add_token(TokenType::LEFTPAREN),
                 ')' => self.add_token(TokenType::RIGHTPAREN),
-                '{' => self.add_token(TokenType::LEFTBRACE),
-                '}' => self.add_token(TokenType::RIGHTBRACE),
-                '+' => self.add_token(TokenType::PLUS),
-                '-' => self.add_token(TokenType::MINUS),
-                '*' => self.add_token(TokenType::STAR),
+                '{' => {
+                    if let Some(string) = self.interpolations.last_mut() {
+                        string.braces += 1;
+                    }
+                    self.add_token(TokenType::LEFTBRACE)
+                }
+                '}' => match self.interpolations.last_mut() {
+                    Some(string) if string.braces == 0 => {
+                        // `${}` has no expression to interpolate.
+                        if string.tokens.last() == Some(&(self.tokens.len() - 1)) {
+                            return Err(LexerError::new(
+                                self.line,
+                                self.offset,
+                                LexerErrorTypes::UnexpectedCharacter('}'),
+                                self.file_name.clone(),
+                                self.source.clone(),
+                            ));
+                        }
+                        self.next();
+                        let string = self.interpolations.pop().unwrap();
+                        self.string_body(string)?;
+                    }
+                    Some(string) => {
+                        string.braces -= 1;
+                        self.add_token(TokenType::RIGHTBRACE)
+                    }
+                    None => self.add_token(TokenType::RIGHTBRACE),
+                },
+                '[' => self.add_token(TokenType::LEFTBRACKET),
+                ']' => self.add_token(TokenType::RIGHTBRACKET),
+                '+' if self.peek('+') => {
+                    self.add_conditional_token('+', TokenType::PLUSPLUS, TokenType::PLUS)
+                }
+                '+' => self.add_conditional_token('=', TokenType::PLUSEQUAL, TokenType::PLUS),
+                '-' if self.peek('-') => {
+                    self.add_conditional_token('-', TokenType::MINUSMINUS, TokenType::MINUS)
+                }
+                '-' => self.add_conditional_token('=', TokenType::MINUSEQUAL, TokenType::MINUS),
+                '*' => self.add_conditional_token('=', TokenType::STAREQUAL, TokenType::STAR),
+                '.' if self.peak_next().is_ascii_digit() => self.number()?,
                 '.' => self.add_token(TokenType::DOT),
                 ',' => self.add_token(TokenType::COMMA),
-                '^' => self.add_token(TokenType::POW),
-                '%' => self.add_token(TokenType::MODULO),
+                '^' => self.add_conditional_token('=', TokenType::POWEQUAL, TokenType::POW),
+                '%' => self.add_conditional_token('=', TokenType::MODULOEQUAL, TokenType::MODULO),
                 ';' => self.add_token(TokenType::SEMICOLON),
+                ':' => self.add_conditional_token('=', TokenType::COLONEQUAL, TokenType::COLON),
                 '!' => self.add_conditional_token('=', TokenType::BANGEQUAL, TokenType::BANG),
                 '=' => self.add_conditional_token('=', TokenType::EQUALEQUAL, TokenType::EQUAL),
                 '<' => self.add_conditional_token('=', TokenType::LESSEQUAL, TokenType::LESS),
                 '>' => self.add_conditional_token('=', TokenType::GREATEREQUAL, TokenType::GREATER),
-                '/' => self.check_for_comments(),
-                '"' => self.string()?,
+                '?' if self.peek('?') => self.add_conditional_token(
+                    '?',
+                    TokenType::QUESTIONQUESTION,
+                    TokenType::QUESTION,
+                ),
+                // `c ?.5 : 1` is a conditional with a number, not `?.`.
+                '?' if !self
+                    .source
+                    .get(self.position + 2)
+                    .is_some_and(|c| c.is_ascii_digit()) =>
+                {
+                    self.add_conditional_token('.', TokenType::QUESTIONDOT, TokenType::QUESTION)
+                }
+                '?' => self.add_token(TokenType::QUESTION),
+                '&' => self.add_conditional_token('&', TokenType::AND, TokenType::AMPERSAND),
+                '|' => self.add_conditional_token('|', TokenType::OR, TokenType::PIPE),
+                '/' => self.check_for_comments()?,
+                '"' => self.string(false)?,
+                'r' if self.peek('"') => {
+                    self.next();
+                    self.string(true)?
+                }
                 _ => {
                     if self.is_digit(c) {
                         self.number()?;
                     } else if self.is_alpha(c) {
                         self.identifier();
-                    }
-                    // TheDevConnor: 2021-05-01 12:00:00
-                    // This is needed to handle new lines and empty spaces
-                    // Which fixes the issue with the lexer not being able to
-                    // handle new lines and empty spaces
-                    else if c == '\n' || c == '\r' || c == '\r' {
-                        self.empty_next();
                     } else {
                         return Err(LexerError::new(
                             self.line,
@@ -417,6 +740,10 @@ impl Scanner {
                 }
             }
         }
+
+        if let Some(string) = self.interpolations.last() {
+            return Err(self.unterminated_string(string));
+        }
         Ok(())
     }
 }
@@ -426,12 +753,483 @@ impl Iterator for Scanner {
 
     fn next(&mut self) -> Option<char> {
         if !self.is_eof() {
-            self.offset += 1;
             let char = self.source[self.position];
+            self.offset = match char {
+                '\t' => (self.offset / self.tab_width + 1) * self.tab_width,
+                _ => self.offset + char.width().unwrap_or(0),
+            };
             self.position += 1;
-            return Some(char);
+            Some(char)
         } else {
-            return None;
+            None
         }
     }
 }
+
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        16 => "hexadecimal",
+        _ => "decimal",
+    }
+}
+
+/// A string literal being scanned. An interpolated string is split into
+/// several tokens, so while an embedded expression is scanned the literal
+/// waits on the scanner's interpolation stack.
+struct StringLiteral {
+    raw: bool,
+    triple: bool,
+    line: usize,
+    offset: usize,
+    /// Each source line of the string, with the number of leading
+    /// whitespace characters that were written literally. Escaped
+    /// whitespace never counts as indentation.
+    lines: Vec<(String, usize)>,
+    /// Where each `${...}` sat, as a line index and character column.
+    holes: Vec<(usize, usize)>,
+    /// The INTERPOLATION tokens emitted so far.
+    tokens: Vec<usize>,
+    /// Braces opened and not yet closed inside the current embedded
+    /// expression.
+    braces: usize,
+}
+
+impl StringLiteral {
+    fn new(raw: bool, triple: bool, line: usize, offset: usize) -> StringLiteral {
+        StringLiteral {
+            raw,
+            triple,
+            line,
+            offset,
+            lines: vec![(String::new(), 0)],
+            holes: Vec::new(),
+            tokens: Vec::new(),
+            braces: 0,
+        }
+    }
+
+    fn push(&mut self, c: char, literal: bool) {
+        let at_indent = literal && (c == ' ' || c == '\t') && self.is_blank(self.lines.len() - 1);
+        let (text, indent) = self.lines.last_mut().unwrap();
+        if at_indent {
+            *indent += 1;
+        }
+        text.push(c);
+    }
+
+    fn start_hole(&mut self, token: usize) {
+        let line = self.lines.len() - 1;
+        self.holes.push((line, self.lines[line].0.chars().count()));
+        self.tokens.push(token);
+        self.braces = 0;
+    }
+
+    fn is_blank(&self, line: usize) -> bool {
+        let (text, indent) = &self.lines[line];
+        text.chars().count() == *indent && !self.holes.iter().any(|(l, _)| *l == line)
+    }
+
+    /// Splits the literal into its text parts, one more than there are
+    /// embedded expressions. Triple-quoted strings lose the indentation
+    /// shared by their lines. A line break straight after the opening
+    /// quotes and a whitespace-only line before the closing quotes are
+    /// dropped, so
+    ///
+    /// ```text
+    /// var s = """
+    ///     hello
+    ///       world
+    ///     """;
+    /// ```
+    ///
+    /// is `"hello\n  world"`. Text on the same line as the opening quotes
+    /// is kept as written and does not take part in the indentation.
+    fn into_parts(self) -> Vec<String> {
+        let mut first = 0;
+        let mut last = self.lines.len();
+        let mut shared = 0;
+        if self.triple {
+            if self.is_blank(0) {
+                first = 1;
+            }
+            if last > first.max(1) && self.is_blank(last - 1) {
+                last -= 1;
+            }
+            shared = (first.max(1)..last)
+                .filter(|&line| !self.is_blank(line))
+                .map(|line| self.lines[line].1)
+                .min()
+                .unwrap_or(0);
+        }
+
+        let mut parts = vec![String::new()];
+        for line in first..last {
+            if line > first {
+                parts.last_mut().unwrap().push('\n');
+            }
+            let (text, indent) = &self.lines[line];
+            let cut = if line == 0 { 0 } else { shared.min(*indent) };
+            let mut holes = self
+                .holes
+                .iter()
+                .filter(|(l, _)| *l == line)
+                .map(|(_, column)| *column)
+                .peekable();
+
+            for (column, c) in text.chars().enumerate() {
+                while holes.next_if(|&hole| hole <= column).is_some() {
+                    parts.push(String::new());
+                }
+                if column >= cut {
+                    parts.last_mut().unwrap().push(c);
+                }
+            }
+            for _ in holes {
+                parts.push(String::new());
+            }
+        }
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scans `source` and returns the type and text of every token but the
+    /// final EOF.
+    fn scan(source: &str) -> Vec<(TokenType, String)> {
+        let mut scanner = Scanner::new(source.chars().collect(), "test".to_string());
+        let mut tokens: Vec<(TokenType, String)> = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| (token.ttype.clone(), token.lexeme.clone()))
+            .collect();
+        assert_eq!(tokens.pop().map(|(ttype, _)| ttype), Some(TokenType::EOF));
+        tokens
+    }
+
+    /// `scan_tokens` reports errors and exits, so this drives `scan_token`
+    /// directly.
+    fn fails(source: &str) -> bool {
+        Scanner::new(source.chars().collect(), "test".to_string())
+            .scan_token()
+            .is_err()
+    }
+
+    fn text(ttype: TokenType, lexeme: &str) -> (TokenType, String) {
+        (ttype, lexeme.to_string())
+    }
+
+    #[test]
+    fn plain_string_has_no_interpolation_tokens() {
+        assert_eq!(scan(r#""a b""#), [text(TokenType::STRING, "a b")]);
+    }
+
+    #[test]
+    fn interpolation_splits_around_the_expression() {
+        assert_eq!(
+            scan(r#""a ${x} b""#),
+            [
+                text(TokenType::INTERPOLATION, "a "),
+                text(TokenType::IDENTIFIER, "x"),
+                text(TokenType::STRING, " b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_interpolation() {
+        assert_eq!(
+            scan(r#""a${"b${x}c"}d""#),
+            [
+                text(TokenType::INTERPOLATION, "a"),
+                text(TokenType::INTERPOLATION, "b"),
+                text(TokenType::IDENTIFIER, "x"),
+                text(TokenType::STRING, "c"),
+                text(TokenType::STRING, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn braces_inside_a_hole_do_not_close_it() {
+        assert_eq!(
+            scan(r#""${{"k": 1}["k"]}!""#),
+            [
+                text(TokenType::INTERPOLATION, ""),
+                text(TokenType::LEFTBRACE, "{"),
+                text(TokenType::STRING, "k"),
+                text(TokenType::COLON, ":"),
+                text(TokenType::NUMBER, "1"),
+                text(TokenType::RIGHTBRACE, "}"),
+                text(TokenType::LEFTBRACKET, "["),
+                text(TokenType::STRING, "k"),
+                text(TokenType::RIGHTBRACKET, "]"),
+                text(TokenType::STRING, "!"),
+            ]
+        );
+    }
+
+    #[test]
+    fn brace_inside_a_nested_string_is_text() {
+        assert_eq!(
+            scan(r#""x${"}"}y""#),
+            [
+                text(TokenType::INTERPOLATION, "x"),
+                text(TokenType::STRING, "}"),
+                text(TokenType::STRING, "y"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_interpolation_is_an_error() {
+        assert!(fails(r#""${}""#));
+        assert!(fails(r#""a${"b${}"}""#));
+    }
+
+    #[test]
+    fn unterminated_interpolation_is_an_error() {
+        assert!(fails(r#""a${x"#));
+        assert!(fails(r#""a${x}"#));
+    }
+
+    #[test]
+    fn escaped_dollar_is_not_a_hole() {
+        assert_eq!(scan(r#""\${x}""#), [text(TokenType::STRING, "${x}")]);
+        assert_eq!(scan(r#"r"${x}""#), [text(TokenType::STRING, "${x}")]);
+    }
+
+    #[test]
+    fn triple_quoted_string_strips_shared_indent_around_holes() {
+        let source = "\"\"\"\n    a ${x}\n      b ${y} c\n    \"\"\"";
+        assert_eq!(
+            scan(source),
+            [
+                text(TokenType::INTERPOLATION, "a "),
+                text(TokenType::IDENTIFIER, "x"),
+                text(TokenType::INTERPOLATION, "\n  b "),
+                text(TokenType::IDENTIFIER, "y"),
+                text(TokenType::STRING, " c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn triple_quoted_hole_at_line_start_keeps_indent_counted() {
+        let source = "\"\"\"\n    ${x}\n    b\n    \"\"\"";
+        assert_eq!(
+            scan(source),
+            [
+                text(TokenType::INTERPOLATION, ""),
+                text(TokenType::IDENTIFIER, "x"),
+                text(TokenType::STRING, "\nb"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escaped_whitespace_is_not_indentation() {
+        let source = "\"\"\"\n    a\n    \\t${x}\n    \"\"\"";
+        assert_eq!(
+            scan(source),
+            [
+                text(TokenType::INTERPOLATION, "a\n\t"),
+                text(TokenType::IDENTIFIER, "x"),
+                text(TokenType::STRING, ""),
+            ]
+        );
+
+        let source = "\"\"\"\n  \\u{20} a\n  b\n  \"\"\"";
+        assert_eq!(scan(source), [text(TokenType::STRING, "  a\nb")]);
+    }
+
+    /// Scans `source` and returns the literal of every number token.
+    fn numbers(source: &str) -> Vec<Object> {
+        let mut scanner = Scanner::new(source.chars().collect(), "test".to_string());
+        scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .filter(|token| token.ttype == TokenType::NUMBER)
+            .map(|token| token.literal.clone())
+            .collect()
+    }
+
+    #[test]
+    fn radix_prefixes_and_underscores() {
+        let values = numbers("0xFF 0o17 0b1010 0XfF 1_000_000 0b1111_0000");
+        let ints: Vec<i64> = values
+            .iter()
+            .map(|value| match value {
+                Object::Int(n) => *n,
+                other => panic!("expected an Int, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(ints, [255, 15, 10, 255, 1_000_000, 240]);
+    }
+
+    #[test]
+    fn fraction_or_exponent_makes_a_float() {
+        let values = numbers("42 6.02e23 1e-3 2E+2 .5 1. 1_0.2_5");
+        assert!(matches!(values[0], Object::Int(42)));
+        let floats: Vec<f64> = values[1..]
+            .iter()
+            .map(|value| match value {
+                Object::Float(n) => *n,
+                other => panic!("expected a Float, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(floats, [6.02e23, 0.001, 200.0, 0.5, 1.0, 10.25]);
+    }
+
+    #[test]
+    fn dot_before_a_name_is_member_access() {
+        assert_eq!(
+            scan("1.foo"),
+            [
+                text(TokenType::NUMBER, "1"),
+                text(TokenType::DOT, "."),
+                text(TokenType::IDENTIFIER, "foo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_numbers_are_errors() {
+        for source in [
+            "0x", "0o", "0b", "0b102", "0o8", "0xG", "0x1.5", "1_", "1__0", "1e", "1e+", "123abc",
+            "1.5x",
+        ] {
+            assert!(fails(source), "{} should not scan", source);
+        }
+    }
+
+    #[test]
+    fn numbers_out_of_range_are_errors() {
+        assert!(fails("99999999999999999999"));
+        assert!(fails("0xFFFFFFFFFFFFFFFFF"));
+        assert!(fails("1e999"));
+    }
+
+    /// Runs `scanner` and returns the text, line and column of every token
+    /// but the final EOF.
+    fn located(mut scanner: Scanner) -> Vec<(String, usize, usize)> {
+        let mut tokens: Vec<(String, usize, usize)> = scanner
+            .scan_tokens()
+            .unwrap()
+            .iter()
+            .map(|token| (token.lexeme.clone(), token.line, token.position))
+            .collect();
+        tokens.pop();
+        tokens
+    }
+
+    fn scanner(source: &str) -> Scanner {
+        Scanner::new(source.chars().collect(), "test".to_string())
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        assert_eq!(
+            scan("a /* one /* two */ still one */ b"),
+            [
+                text(TokenType::IDENTIFIER, "a"),
+                text(TokenType::IDENTIFIER, "b"),
+            ]
+        );
+        assert_eq!(scan("/**/x/***/"), [text(TokenType::IDENTIFIER, "x")]);
+    }
+
+    #[test]
+    fn lines_inside_a_block_comment_are_counted() {
+        assert_eq!(
+            located(scanner("/* a\n /* b\n */ */ x")),
+            [("x".to_string(), 2, 8)]
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comment_is_an_error() {
+        assert!(fails("/* a"));
+        assert!(fails("/* a /* b */"));
+    }
+
+    #[test]
+    fn doc_comments_become_tokens() {
+        assert_eq!(
+            scan("/// Adds one.\n// plain\n//// banner\nx"),
+            [
+                text(TokenType::DOCCOMMENT, "Adds one."),
+                text(TokenType::IDENTIFIER, "x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn slash_is_still_division() {
+        assert_eq!(
+            scan("a / b /= c"),
+            [
+                text(TokenType::IDENTIFIER, "a"),
+                text(TokenType::SLASH, "/"),
+                text(TokenType::IDENTIFIER, "b"),
+                text(TokenType::SLASHEQUAL, "/="),
+                text(TokenType::IDENTIFIER, "c"),
+            ]
+        );
+    }
+
+    fn at(lexeme: &str, line: usize, column: usize) -> (String, usize, usize) {
+        (lexeme.to_string(), line, column)
+    }
+
+    #[test]
+    fn byte_order_mark_is_skipped() {
+        assert_eq!(
+            located(scanner("\u{FEFF}var x")),
+            [at("var", 0, 1), at("x", 0, 5)]
+        );
+    }
+
+    #[test]
+    fn shebang_line_is_skipped() {
+        assert_eq!(located(scanner("#!/usr/bin/env kn\nx")), [at("x", 1, 1)]);
+        assert_eq!(
+            located(scanner("\u{FEFF}#!/usr/bin/env kn\r\nx")),
+            [at("x", 1, 1)]
+        );
+    }
+
+    #[test]
+    fn crlf_and_lone_cr_each_end_one_line() {
+        assert_eq!(
+            located(scanner("a\r\nb\rc\n\r\nd")),
+            [at("a", 0, 1), at("b", 1, 1), at("c", 2, 1), at("d", 4, 1)]
+        );
+    }
+
+    #[test]
+    fn tabs_advance_to_the_next_tab_stop() {
+        assert_eq!(
+            located(scanner("\tx\n  \ty\n\t\tz")),
+            [at("x", 0, 5), at("y", 1, 5), at("z", 2, 9)]
+        );
+        assert_eq!(
+            located(scanner("\tx\n  \ty").with_tab_width(8)),
+            [at("x", 0, 9), at("y", 1, 9)]
+        );
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(
+            located(scanner("\"変数\" x")),
+            [at("変数", 0, 1), at("x", 0, 8)]
+        );
+    }
+}
@@ -10,7 +10,11 @@ pub struct Token {
     pub lexeme: String,
     pub literal: Object,
     pub line: usize,
+    /// The 1-based display column of the token's first character,
+    /// counting wide characters as two and tabs up to the next tab stop.
     pub position: usize,
+    /// Where the token starts in the UTF-8 source, in bytes.
+    pub byte_offset: usize,
 }
 
 impl Token {
@@ -20,6 +24,7 @@ impl Token {
         literal: Object,
         line: usize,
         position: usize,
+        byte_offset: usize,
     ) -> Self {
         Token {
             ttype,
@@ -27,6 +32,7 @@ impl Token {
             literal,
             line,
             position,
+            byte_offset,
         }
     }
 }
@@ -39,11 +45,17 @@ impl fmt::Display for Token {
             self.ttype,
             self.lexeme,
             match &self.literal {
-                Object::Num(x) => x.to_string(),
+                Object::Int(_) | Object::Float(_) => self.literal.to_string(),
                 Object::Str(x) => format!("\"{}\"", x),
+                Object::List(_)
+                | Object::Map(_)
+                | Object::Func(_)
+                | Object::Class(_)
+                | Object::Instance(_)
+                | Object::Native(_) => self.literal.to_string(),
                 Object::Nil => "Nil".to_string(),
                 Object::Bool(x) => {
-                    if *x == true {
+                    if *x {
                         "True".to_string()
                     } else {
                         "False".to_string()
@@ -65,10 +77,12 @@ pub static KEYWORDS: Lazy<HashMap<String, TokenType>> = Lazy::new(|| {
     map.insert(String::from("func"), TokenType::FUNC);
     map.insert(String::from("else"), TokenType::ELSE);
     map.insert(String::from("class"), TokenType::CLASS);
+    map.insert(String::from("super"), TokenType::SUPER);
+    map.insert(String::from("this"), TokenType::THIS);
     map.insert(String::from("if"), TokenType::IF);
-    map.insert(String::from("&&"), TokenType::AND);
-    map.insert(String::from("||"), TokenType::OR);
     map.insert(String::from("while"), TokenType::WHILE);
+    map.insert(String::from("break"), TokenType::BREAK);
+    map.insert(String::from("continue"), TokenType::CONTINUE);
     map.insert(String::from("show"), TokenType::PRINT);
     map.insert(String::from("ret"), TokenType::RETURN);
     map.insert(String::from("null"), TokenType::NIL);
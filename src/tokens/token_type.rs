@@ -1,3 +1,4 @@
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenType {
     // Single-character tokens.
@@ -5,11 +6,14 @@ pub enum TokenType {
     RIGHTPAREN,
     LEFTBRACE,
     RIGHTBRACE,
+    LEFTBRACKET,
+    RIGHTBRACKET,
     COMMA,
     DOT,
     MINUS,
     PLUS,
     SEMICOLON,
+    COLON,
     SLASH,
     STAR,
     POW,
@@ -18,32 +22,50 @@ pub enum TokenType {
     // One or two character tokens.
     BANG,
     BANGEQUAL,
+    COLONEQUAL,
+    PLUSEQUAL,
+    PLUSPLUS,
+    MINUSEQUAL,
+    MINUSMINUS,
+    STAREQUAL,
+    SLASHEQUAL,
+    POWEQUAL,
+    MODULOEQUAL,
     EQUAL,
     EQUALEQUAL,
     GREATER,
     GREATEREQUAL,
     LESS,
     LESSEQUAL,
+    QUESTION,
+    QUESTIONQUESTION,
+    QUESTIONDOT,
+    AMPERSAND,
+    AND,
+    PIPE,
+    OR,
 
     // Literals.
     IDENTIFIER,
     STRING,
+    INTERPOLATION,
     NUMBER,
+    DOCCOMMENT,
 
     // Keywords.
-    AND,
+    BREAK,
     CLASS,
+    CONTINUE,
     ELSE,
     FALSE,
     FUNC,
     FOR,
     IF,
     NIL,
-    OR,
     PRINT,
     RETURN,
-    // SUPER,
-    // THIS,
+    SUPER,
+    THIS,
     TRUE,
     HAVE,
     VAR,
@@ -0,0 +1,44 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Feeds `input` to the interactive prompt and returns what it printed.
+/// Panics if the prompt has not exited within a few seconds.
+fn run_prompt(input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_kn"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("could not start the interpreter");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(child.wait_with_output());
+    });
+    let output = receiver
+        .recv_timeout(Duration::from_secs(5))
+        .expect("the prompt did not finish")
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn input_reads_the_next_line_at_the_prompt() {
+    let output = run_prompt("var x = input(\"name? \");\nabc\nshow x;\n");
+    assert_eq!(output, "> name? > abc\n> ");
+}
+
+#[test]
+fn prompt_keeps_state_between_lines() {
+    let output = run_prompt("var x = 1;\nx += 2;\nshow x;\n");
+    assert_eq!(output, "> > > 3\n> ");
+}
@@ -0,0 +1,181 @@
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// What running a script printed, and the exit code it stopped with.
+struct Run {
+    stdout: String,
+    stderr: String,
+    code: i32,
+}
+
+/// Writes `source` to a temporary file and runs it like `kn file`.
+fn run(source: &str) -> Run {
+    let path = std::env::temp_dir().join(format!(
+        "kn-test-{}-{}.cdr",
+        std::process::id(),
+        NEXT_FILE.fetch_add(1, Ordering::SeqCst)
+    ));
+    std::fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_kn"))
+        .arg(&path)
+        .output()
+        .expect("could not start the interpreter");
+    let _ = std::fs::remove_file(&path);
+    Run {
+        stdout: String::from_utf8(output.stdout).unwrap(),
+        stderr: String::from_utf8(output.stderr).unwrap(),
+        code: output.status.code().unwrap_or(-1),
+    }
+}
+
+#[test]
+fn assigning_to_a_local_constant_is_a_syntax_error() {
+    let output = run("show \"before\"; { have c = 1; c = 2; }");
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.code, 64);
+    assert!(output.stderr.contains("Syntax Error"), "{}", output.stderr);
+    assert!(output.stderr.contains("Cannot assign to constant \"c\""));
+}
+
+#[test]
+fn assigning_to_a_constant_through_a_closure_is_a_syntax_error() {
+    let output = run(
+        "func outer() { have c = 1; func inner() { c += 1; } ret inner; } \
+         show \"before\";",
+    );
+    assert_eq!(output.stdout, "");
+    assert_eq!(output.code, 64);
+    assert!(output.stderr.contains("Cannot assign to constant \"c\""));
+
+    let output = run("func outer() { have c = 1; func inner() { c++; } ret inner; }");
+    assert_eq!(output.code, 64);
+}
+
+#[test]
+fn assigning_to_a_later_global_constant_is_a_runtime_error() {
+    let output = run("show \"before\"; func f() { x = 5; } have x := 1; f(); show x;");
+    assert_eq!(output.stdout, "before\n");
+    assert_eq!(output.code, 70);
+    assert!(output.stderr.contains("Runtime Error"), "{}", output.stderr);
+    assert!(output.stderr.contains("Cannot assign to constant \"x\""));
+}
+
+#[test]
+fn variables_stay_assignable() {
+    let output = run(
+        "var x = 1; func f() { x = 5; } f(); show x; have y = 2; { var y = 3; y = 4; show y; }",
+    );
+    assert_eq!(output.stdout, "5\n4\n");
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn deep_recursion_is_a_located_runtime_error() {
+    let output = run("func f(n) { ret f(n + 1); }\nshow \"start\";\nf(0);");
+    assert_eq!(output.stdout, "start\n");
+    assert_eq!(output.code, 70);
+    assert!(
+        output.stderr.starts_with("[1:24] Runtime Error"),
+        "{}",
+        output.stderr
+    );
+    assert!(output.stderr.contains("Stack overflow"));
+}
+
+#[test]
+fn recursion_below_the_limit_runs() {
+    let output = run(
+        "func f(n) { if (n == 0) ret 0; { { ret f(n - 1) + 1; } } }\n\
+         class Node { init(n) { if (n > 0) this.next = Node(n - 1); } }\n\
+         show f(990); Node(990); show \"built\";",
+    );
+    assert_eq!(output.stdout, "990\nbuilt\n");
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn safe_navigation_skips_the_rest_of_the_chain() {
+    let output = run(
+        "var o = null; var calls = 0; func count() { calls++; ret 1; }\n\
+         show o?.m(count()); show o?.a.b.c(); show o?.a.b ?? \"fallback\"; show calls;",
+    );
+    assert_eq!(output.stdout, "Nil\nNil\nfallback\n0\n");
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn safe_navigation_stops_where_it_finds_null() {
+    let output = run(
+        "class P { init(n) { this.next = n; } v() { ret \"v\"; } }\n\
+         var p = P(P(null));\n\
+         show p.next?.v(); show p.next?.next?.v().length; show p.next.next?.next.next;",
+    );
+    assert_eq!(output.stdout, "v\nNil\nNil\n");
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn parentheses_end_a_safe_navigation_chain() {
+    let output = run("var o = null;\nshow (o?.a).b;");
+    assert_eq!(output.code, 70);
+    assert!(output.stderr.contains("Only instances have properties"));
+}
+
+#[test]
+fn safe_navigation_is_not_an_assignment_target() {
+    let output = run("var o = null;\no?.a = 1;");
+    assert_eq!(output.code, 64);
+    assert!(output.stderr.contains("Invalid assignment target"));
+}
+
+#[test]
+fn labelled_break_and_continue_leave_the_named_loop() {
+    let output = run("outer: for (var i = 0; i < 3; i++) {\n\
+           for (var j = 0; j < 3; j++) {\n\
+             if (j == 1) continue outer;\n\
+             if (i == 2) break outer;\n\
+             show i * 10 + j;\n\
+           }\n\
+         }\n\
+         var k = 0; while (true) { k++; if (k < 3) continue; break; } show k;");
+    assert_eq!(output.stdout, "0\n10\n3\n");
+    assert_eq!(output.code, 0);
+}
+
+#[test]
+fn loop_control_cannot_leave_a_function_or_method() {
+    for source in [
+        "break;",
+        "while (true) { func f() { break; } }",
+        "while (true) { class C { m() { continue; } } }",
+    ] {
+        let output = run(source);
+        assert_eq!(output.code, 64, "{}", source);
+        assert!(
+            output.stderr.contains("outside of a loop"),
+            "{}",
+            output.stderr
+        );
+    }
+
+    let output = run("class A { m() { ret 1; } }\n\
+         class B < A { m() { while (true) { ret super.m(); } } }\n\
+         show B().m();");
+    assert_eq!(output.stdout, "1\n");
+}
+
+#[test]
+fn unknown_loop_label_is_a_syntax_error() {
+    let output = run("a: while (true) { break b; }");
+    assert_eq!(output.code, 64);
+    assert!(
+        output.stderr.starts_with("[1:25] Syntax Error"),
+        "{}",
+        output.stderr
+    );
+    assert!(output
+        .stderr
+        .contains("No enclosing loop is labelled \"b\""));
+}